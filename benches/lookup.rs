@@ -0,0 +1,61 @@
+//! Compares looking names up by scanning against looking them up in the `HashMap`,
+//! to find the size at which `NamedVec` should start building its index.
+//!
+//! Run with `cargo bench --bench lookup`.
+
+extern crate named_vec;
+
+use named_vec::{Named, NamedVec};
+use std::hint::black_box;
+use std::time::Instant;
+
+const LOOKUPS: usize = 1_000_000;
+
+struct Item(String);
+
+impl Named for Item {
+    fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+fn build(len: usize, threshold: usize) -> (NamedVec<Item>, Vec<String>) {
+    let names: Vec<String> = (0..len).map(|i| format!("element_{}", i)).collect();
+    let mut named_vec = NamedVec::with_index_threshold(threshold);
+    for name in &names {
+        named_vec.push(Item(name.clone()));
+    }
+    (named_vec, names)
+}
+
+fn nanos_per_lookup(named_vec: &NamedVec<Item>, names: &[String]) -> f64 {
+    let start = Instant::now();
+    for i in 0..LOOKUPS {
+        black_box(named_vec.get(names[i % names.len()].as_str()));
+    }
+    start.elapsed().as_secs_f64() * 1e9 / LOOKUPS as f64
+}
+
+fn nanos_per_build(len: usize, threshold: usize) -> f64 {
+    let rounds = LOOKUPS / len;
+    let start = Instant::now();
+    for _ in 0..rounds {
+        black_box(build(len, threshold));
+    }
+    start.elapsed().as_secs_f64() * 1e9 / rounds as f64
+}
+
+fn main() {
+    println!("{:>5} {:>12} {:>12} {:>12} {:>12}",
+             "len", "scan get", "hash get", "scan build", "hash build");
+    for &len in &[1, 2, 4, 8, 12, 16, 24, 32, 48, 64] {
+        let (scanned, names) = build(len, usize::MAX);
+        let (indexed, _) = build(len, 0);
+        println!("{:>5} {:>10.1}ns {:>10.1}ns {:>10.1}ns {:>10.1}ns",
+                 len,
+                 nanos_per_lookup(&scanned, &names),
+                 nanos_per_lookup(&indexed, &names),
+                 nanos_per_build(len, usize::MAX),
+                 nanos_per_build(len, 0));
+    }
+}
@@ -0,0 +1,363 @@
+//! Fixed-capacity `NamedVec` that never allocates.
+
+use core::ops::Index;
+
+use {fnv1a, Lookup, Named};
+
+/// Vector of at most `N` named elements, stored inline.
+///
+/// `ArrayNamedVec` mirrors [`NamedVec`](../struct.NamedVec.html)'s API, but keeps its elements
+/// in an array and its name index in a fixed-size open-addressed hash table, so it never
+/// allocates. [`push()`](#method.push) and [`insert()`](#method.insert) panic if the collection
+/// is full; use [`try_push()`](#method.try_push) to get the element back instead.
+///
+/// `Handle`s are not supported; looking up a `Handle` always returns `None`.
+#[derive(Debug, Clone)]
+pub struct ArrayNamedVec<T: Named, const N: usize> {
+    items: [Option<T>; N],
+    len: usize,
+    // Linear probing table of indices into `items`
+    table: [Option<usize>; N],
+}
+
+enum Probe {
+    Found(usize),
+    Vacant(usize),
+    Full,
+}
+
+impl<T: Named, const N: usize> ArrayNamedVec<T, N> {
+    /// Creates an empty `ArrayNamedVec<T, N>`.
+    pub fn new() -> Self {
+        ArrayNamedVec {
+            items: core::array::from_fn(|_| None),
+            len: 0,
+            table: [None; N],
+        }
+    }
+
+    /// Returns the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the vector holds `N` elements.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Returns the number of elements the vector can hold, which is always `N`.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Appends an element to the back of the collection,
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a new element would exceed the capacity.
+    pub fn push(&mut self, value: T) {
+        if self.try_push(value).is_err() {
+            panic!("ArrayNamedVec is full");
+        }
+    }
+
+    /// Appends an element to the back of the collection,
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// Returns `Err(value)` if the element is new and the collection is full.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        match self.probe(value.name()) {
+            Probe::Found(slot) => {
+                let i = self.table[slot].unwrap();
+                self.items[i] = Some(value);
+                Ok(())
+            },
+            Probe::Vacant(slot) if !self.is_full() => {
+                self.table[slot] = Some(self.len);
+                self.items[self.len] = Some(value);
+                self.len += 1;
+                Ok(())
+            },
+            _ => {
+                Err(value)
+            },
+        }
+    }
+
+    /// Inserts an element at position `index` (shifting all elements after it to the right),
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if `index` is out of bounds.
+    /// * Panics if a new element would exceed the capacity.
+    pub fn insert(&mut self, index: usize, value: T) {
+        let slot = match self.probe(value.name()) {
+            Probe::Found(slot) => {
+                let i = self.table[slot].unwrap();
+                self.items[i] = Some(value);
+                return;
+            },
+            Probe::Vacant(slot) if !self.is_full() => slot,
+            _ => panic!("ArrayNamedVec is full"),
+        };
+        assert!(index <= self.len, "insertion index (is {}) should be <= len (is {})",
+                index, self.len);
+
+        self.items[self.len] = Some(value);
+        self.items[index..self.len + 1].rotate_right(1);
+        for i in self.table.iter_mut().flatten() {
+            if *i >= index {
+                *i += 1;
+            }
+        }
+        self.table[slot] = Some(index);
+        self.len += 1;
+    }
+
+    /// Removes and returns the element specified by `lookup`
+    /// within the vector, shifting all elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if a `usize` argument is out of bounds.
+    /// * Panics if a `&str` argument is an invalid name.
+    /// * Panics if given a `Handle`.
+    pub fn remove<'a, A>(&mut self, lookup: A) -> T where A: 'a + Into<Lookup<'a>> {
+        let index = self.index_from_lookup(lookup.into()).expect("invalid lookup");
+
+        self.unlink(index);
+        self.items[index..self.len].rotate_left(1);
+        for i in self.table.iter_mut().flatten() {
+            if *i > index {
+                *i -= 1;
+            }
+        }
+        self.len -= 1;
+        self.items[self.len].take().unwrap()
+    }
+
+    /// Removes the last element from the vector and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.unlink(self.len - 1);
+            self.len -= 1;
+            self.items[self.len].take()
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    pub fn clear(&mut self) {
+        for item in &mut self.items[..self.len] {
+            *item = None;
+        }
+        self.table = [None; N];
+        self.len = 0;
+    }
+
+    /// Returns a reference to an element.
+    ///
+    /// See [`NamedVec::get()`](../struct.NamedVec.html#method.get) for more information.
+    pub fn get<'a, A>(&self, lookup: A) -> Option<&T> where A: 'a + Into<Lookup<'a>> {
+        self.index_from_lookup(lookup.into()).and_then(|i| self.items[i].as_ref())
+    }
+
+    /// Returns a mutable reference to an element.
+    ///
+    /// See [`NamedVec::get()`](../struct.NamedVec.html#method.get) for more information.
+    pub fn get_mut<'a, A>(&mut self, lookup: A) -> Option<&mut T> where A: 'a + Into<Lookup<'a>> {
+        self.index_from_lookup(lookup.into()).and_then(move |i| self.items[i].as_mut())
+    }
+
+    /// Swaps two elements.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if a `usize` argument is out of bounds.
+    /// * Panics if a `&str` argument is an invalid name.
+    /// * Panics if given a `Handle`.
+    pub fn swap<'a, 'b, A, B>(&mut self, first: A, second: B)
+    where A: 'a + Into<Lookup<'a>>, B: 'b + Into<Lookup<'b>> {
+        let i1 = self.index_from_lookup(first.into()).expect("invalid lookup");
+        let i2 = self.index_from_lookup(second.into()).expect("invalid lookup");
+
+        if i1 == i2 {
+            return;
+        }
+
+        let s1 = self.slot_of(i1);
+        let s2 = self.slot_of(i2);
+        self.table[s1] = Some(i2);
+        self.table[s2] = Some(i1);
+        self.items.swap(i1, i2);
+    }
+
+    /// Returns an iterator over the elements in order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            items: self.items[..self.len].iter(),
+        }
+    }
+
+    fn index_from_lookup(&self, lookup: Lookup) -> Option<usize> {
+        match lookup {
+            Lookup::Name(name) => {
+                match self.probe(name) {
+                    Probe::Found(slot) => self.table[slot],
+                    _ => None,
+                }
+            },
+            Lookup::Index(index) => {
+                if index < self.len {
+                    Some(index)
+                } else {
+                    None
+                }
+            },
+            Lookup::Handle(_) => {
+                None
+            },
+        }
+    }
+
+    fn name_at(&self, index: usize) -> &str {
+        self.items[index].as_ref().unwrap().name()
+    }
+
+    fn home_slot(name: &str) -> usize {
+        (fnv1a(name.as_bytes()) % N as u64) as usize
+    }
+
+    fn probe(&self, name: &str) -> Probe {
+        if N == 0 {
+            return Probe::Full;
+        }
+
+        let home = Self::home_slot(name);
+        for step in 0..N {
+            let slot = (home + step) % N;
+            match self.table[slot] {
+                Some(i) if self.name_at(i) == name => return Probe::Found(slot),
+                Some(_) => continue,
+                None => return Probe::Vacant(slot),
+            }
+        }
+        Probe::Full
+    }
+
+    fn slot_of(&self, index: usize) -> usize {
+        match self.probe(self.name_at(index)) {
+            Probe::Found(slot) => slot,
+            _ => unreachable!(),
+        }
+    }
+
+    // Removes the element at `index` from the name index, shifting later entries
+    // of its probe sequence back so that lookups don't stop at the hole
+    fn unlink(&mut self, index: usize) {
+        let mut hole = self.slot_of(index);
+        self.table[hole] = None;
+
+        let mut slot = hole;
+        loop {
+            slot = (slot + 1) % N;
+            let i = match self.table[slot] {
+                Some(i) => i,
+                None => break,
+            };
+
+            let home = Self::home_slot(self.name_at(i));
+            if (slot + N - home) % N >= (slot + N - hole) % N {
+                self.table[hole] = self.table[slot].take();
+                hole = slot;
+            }
+        }
+    }
+}
+
+impl<T: Named, const N: usize> Default for ArrayNamedVec<T, N> {
+    fn default() -> Self {
+        ArrayNamedVec::new()
+    }
+}
+
+impl<T: Named + PartialEq, const N: usize> PartialEq for ArrayNamedVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<T: Named, const N: usize> Index<usize> for ArrayNamedVec<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T: Named, const N: usize> Index<&str> for ArrayNamedVec<T, N> {
+    type Output = T;
+
+    fn index(&self, index: &str) -> &T {
+        self.get(index).unwrap()
+    }
+}
+
+///////////////
+// Iterators //
+///////////////
+
+impl<'a, T: Named, const N: usize> IntoIterator for &'a ArrayNamedVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Named, const N: usize> IntoIterator for ArrayNamedVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            items: IntoIterator::into_iter(self.items),
+        }
+    }
+}
+
+/// Iterator over references to the items in an `ArrayNamedVec<T, N>`
+pub struct Iter<'a, T: 'a> {
+    items: core::slice::Iter<'a, Option<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next().and_then(Option::as_ref)
+    }
+}
+
+/// Iterator over the items in an `ArrayNamedVec<T, N>`
+pub struct IntoIter<T, const N: usize> {
+    items: core::array::IntoIter<Option<T>, N>,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next().flatten()
+    }
+}
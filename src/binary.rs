@@ -0,0 +1,384 @@
+//! Compact binary encoding of `NamedVec`s, readable without deserializing.
+//!
+//! An encoded `NamedVec` stores every element's name and data along with a hash index of the
+//! names, so a [`NamedVecRef`](struct.NamedVecRef.html) can look elements up directly in the
+//! encoded bytes (e.g. a memory-mapped file). Element data is opaque bytes produced and
+//! consumed by the caller.
+//!
+//! # Format
+//!
+//! All integers are little-endian `u32`s unless noted otherwise.
+//!
+//! * Header (24 bytes): the magic bytes `NVEC`, a `u16` version (currently 1), a `u16` of
+//!   reserved flags, the number of elements, the number of hash table slots, the length of
+//!   the blob and a CRC-32 of everything except the checksum itself.
+//! * One 16-byte entry per element, in order: name offset, name length, data offset and
+//!   data length. Offsets are relative to the start of the blob.
+//! * The hash table: one slot per `u32`, holding an entry number plus one, or 0 if empty.
+//!   Names are hashed with 64-bit FNV-1a and placed by linear probing.
+//! * The blob of names and data.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::str;
+
+use {fnv1a, Lookup, Named, NamedVec};
+
+const MAGIC: &[u8; 4] = b"NVEC";
+const VERSION: u16 = 1;
+const HEADER_LEN: usize = 24;
+const CHECKSUM_OFFSET: usize = 20;
+const ENTRY_LEN: usize = 16;
+
+/// Error returned when bytes are not a valid encoded `NamedVec`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DecodeError {
+    /// The input does not start with the expected magic bytes.
+    BadMagic,
+    /// The input was encoded with an unknown version of the format.
+    UnsupportedVersion(u16),
+    /// The input is shorter than its header says.
+    Truncated { expected: usize, found: usize },
+    /// The checksum does not match the contents.
+    ChecksumMismatch { expected: u32, found: u32 },
+    /// The contents are inconsistent despite a matching checksum.
+    Corrupt(&'static str),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::BadMagic => {
+                write!(f, "not an encoded NamedVec")
+            },
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            },
+            DecodeError::Truncated { expected, found } => {
+                write!(f, "expected {} bytes, found {}", expected, found)
+            },
+            DecodeError::ChecksumMismatch { expected, found } => {
+                write!(f, "checksum is {:08x}, expected {:08x}", found, expected)
+            },
+            DecodeError::Corrupt(message) => {
+                write!(f, "corrupt input: {}", message)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Encodes `named_vec`, using `encode` to append each element's data to the given buffer.
+///
+/// # Panics
+///
+/// Panics if the encoded names and data add up to more than 4 GiB.
+pub fn encode<T, F>(named_vec: &NamedVec<T>, mut encode: F) -> Vec<u8>
+where T: Named, F: FnMut(&T, &mut Vec<u8>) {
+    let count = named_vec.len();
+    let table_len = if count == 0 { 0 } else { (count * 2).next_power_of_two() };
+
+    let mut entries = Vec::with_capacity(count * ENTRY_LEN);
+    let mut blob = Vec::new();
+    for item in named_vec {
+        let name_offset = blob.len();
+        blob.extend_from_slice(item.name().as_bytes());
+        let data_offset = blob.len();
+        encode(item, &mut blob);
+
+        push_u32(&mut entries, name_offset);
+        push_u32(&mut entries, data_offset - name_offset);
+        push_u32(&mut entries, data_offset);
+        push_u32(&mut entries, blob.len() - data_offset);
+    }
+
+    let mut table = alloc::vec![0u32; table_len];
+    for (i, item) in named_vec.into_iter().enumerate() {
+        let mut slot = fnv1a(item.name().as_bytes()) as usize & (table_len - 1);
+        while table[slot] != 0 {
+            slot = (slot + 1) & (table_len - 1);
+        }
+        table[slot] = i as u32 + 1;
+    }
+
+    let mut out = Vec::with_capacity(HEADER_LEN + entries.len() + table_len * 4 + blob.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    push_u32(&mut out, count);
+    push_u32(&mut out, table_len);
+    push_u32(&mut out, blob.len());
+    push_u32(&mut out, 0);
+    out.extend_from_slice(&entries);
+    for slot in table {
+        push_u32(&mut out, slot as usize);
+    }
+    out.extend_from_slice(&blob);
+
+    let checksum = checksum(&out);
+    out[CHECKSUM_OFFSET..HEADER_LEN].copy_from_slice(&checksum.to_le_bytes());
+    out
+}
+
+/// An element of a [`NamedVecRef`](struct.NamedVecRef.html).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Entry<'a> {
+    name: &'a str,
+    data: &'a [u8],
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the element's name.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns the element's data, as written by the function given to
+    /// [`encode()`](fn.encode.html).
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> Named for Entry<'a> {
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+/// Read-only view of an encoded `NamedVec` that borrows its bytes.
+///
+/// Creating a `NamedVecRef` validates the whole input once. After that, looking up an
+/// element by name uses the stored hash index and copies nothing.
+#[derive(Debug, Clone, Copy)]
+pub struct NamedVecRef<'a> {
+    entries: &'a [u8],
+    table: &'a [u8],
+    blob: &'a [u8],
+}
+
+impl<'a> NamedVecRef<'a> {
+    /// Validates `bytes` and returns a view of the `NamedVec` they encode.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        if bytes.len() < HEADER_LEN {
+            if bytes.len() >= MAGIC.len() && &bytes[..MAGIC.len()] != MAGIC {
+                return Err(DecodeError::BadMagic);
+            }
+            return Err(DecodeError::Truncated { expected: HEADER_LEN, found: bytes.len() });
+        }
+        if &bytes[..MAGIC.len()] != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let count = read_u32(bytes, 8);
+        let table_len = read_u32(bytes, 12);
+        let blob_len = read_u32(bytes, 16);
+        // The sizes come from the input, so they can overflow on 32-bit targets
+        let expected = count.checked_mul(ENTRY_LEN)
+            .and_then(|entries_len| entries_len.checked_add(HEADER_LEN))
+            .and_then(|len| len.checked_add(table_len.checked_mul(4)?))
+            .and_then(|len| len.checked_add(blob_len))
+            .ok_or(DecodeError::Corrupt("header sizes are too large"))?;
+        if bytes.len() < expected {
+            return Err(DecodeError::Truncated { expected, found: bytes.len() });
+        }
+        let bytes = &bytes[..expected];
+
+        let stored = read_u32(bytes, CHECKSUM_OFFSET) as u32;
+        let computed = checksum(bytes);
+        if stored != computed {
+            return Err(DecodeError::ChecksumMismatch { expected: stored, found: computed });
+        }
+
+        let table_start = HEADER_LEN + count * ENTRY_LEN;
+        let blob_start = table_start + table_len * 4;
+        let named_vec_ref = NamedVecRef {
+            entries: &bytes[HEADER_LEN..table_start],
+            table: &bytes[table_start..blob_start],
+            blob: &bytes[blob_start..],
+        };
+        named_vec_ref.validate(count, table_len)?;
+        Ok(named_vec_ref)
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.entries.len() / ENTRY_LEN
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an element.
+    ///
+    /// This function's argument can be a `usize` index or a `&str` name.
+    /// Returns `None` if there is no such element, or if given a `Handle`.
+    pub fn get<'b, A>(&self, lookup: A) -> Option<Entry<'a>> where A: 'b + Into<Lookup<'b>> {
+        match lookup.into() {
+            Lookup::Name(name) => self.position(name).map(|i| self.entry(i)),
+            Lookup::Index(index) if index < self.len() => Some(self.entry(index)),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the elements in order.
+    pub fn iter(&self) -> Iter<'a> {
+        Iter {
+            named_vec_ref: *self,
+            index: 0,
+        }
+    }
+
+    /// Decodes every element with `decode` and collects them into a `NamedVec<T>`,
+    /// stopping at the first error.
+    pub fn to_named_vec<T, E, F>(&self, decode: F) -> Result<NamedVec<T>, E>
+    where T: Named, F: FnMut(Entry<'a>) -> Result<T, E> {
+        let mut named_vec = NamedVec::with_capacity(self.len());
+        for item in self.iter().map(decode) {
+            named_vec.push(item?);
+        }
+        Ok(named_vec)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        let table_len = self.table.len() / 4;
+        if table_len == 0 {
+            return None;
+        }
+
+        let mut slot = fnv1a(name.as_bytes()) as usize & (table_len - 1);
+        loop {
+            match read_u32(self.table, slot * 4) {
+                0 => return None,
+                n if self.name_bytes(n - 1) == name.as_bytes() => return Some(n - 1),
+                _ => slot = (slot + 1) & (table_len - 1),
+            }
+        }
+    }
+
+    fn entry(&self, index: usize) -> Entry<'a> {
+        let offset = index * ENTRY_LEN;
+        let data_start = read_u32(self.entries, offset + 8);
+        let data_len = read_u32(self.entries, offset + 12);
+        Entry {
+            name: str::from_utf8(self.name_bytes(index)).expect("names are validated"),
+            data: &self.blob[data_start..data_start + data_len],
+        }
+    }
+
+    fn name_bytes(&self, index: usize) -> &'a [u8] {
+        let offset = index * ENTRY_LEN;
+        let start = read_u32(self.entries, offset);
+        let len = read_u32(self.entries, offset + 4);
+        &self.blob[start..start + len]
+    }
+
+    // Checks everything that lookups rely on, so that they can't panic or loop forever
+    fn validate(&self, count: usize, table_len: usize) -> Result<(), DecodeError> {
+        if count > 0 && (!table_len.is_power_of_two() || table_len <= count) {
+            return Err(DecodeError::Corrupt("hash table has the wrong size"));
+        }
+
+        for i in 0..count {
+            let offset = i * ENTRY_LEN;
+            for &(start, len) in &[(0, 4), (8, 12)] {
+                let start = read_u32(self.entries, offset + start);
+                let len = read_u32(self.entries, offset + len);
+                if len > self.blob.len() || start > self.blob.len() - len {
+                    return Err(DecodeError::Corrupt("entry points outside the blob"));
+                }
+            }
+            if str::from_utf8(self.name_bytes(i)).is_err() {
+                return Err(DecodeError::Corrupt("name is not valid UTF-8"));
+            }
+        }
+
+        let mut indexed = 0;
+        for slot in 0..table_len {
+            match read_u32(self.table, slot * 4) {
+                0 => {},
+                n if n <= count => indexed += 1,
+                _ => return Err(DecodeError::Corrupt("hash table points past the last entry")),
+            }
+        }
+        if indexed != count {
+            return Err(DecodeError::Corrupt("hash table does not index every entry"));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for NamedVecRef<'a> {
+    type Item = Entry<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of a `NamedVecRef`
+pub struct Iter<'a> {
+    named_vec_ref: NamedVecRef<'a>,
+    index: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Entry<'a>;
+
+    fn next(&mut self) -> Option<Entry<'a>> {
+        let entry = self.named_vec_ref.get(self.index)?;
+        self.index += 1;
+        Some(entry)
+    }
+}
+
+fn push_u32(out: &mut Vec<u8>, n: usize) {
+    assert!(n <= u32::MAX as usize, "encoded NamedVec is too large");
+    out.extend_from_slice(&(n as u32).to_le_bytes());
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> usize {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(buf) as usize
+}
+
+// CRC-32 (IEEE) of `bytes`, skipping the checksum field in the header
+fn checksum(bytes: &[u8]) -> u32 {
+    let crc = crc32_update(!0, &bytes[..CHECKSUM_OFFSET]);
+    !crc32_update(crc, &bytes[HEADER_LEN..])
+}
+
+fn crc32_update(mut crc: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        crc = CRC32_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
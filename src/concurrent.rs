@@ -0,0 +1,199 @@
+use std::collections::hash_map::{DefaultHasher, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use std::sync::PoisonError;
+
+#[cfg(loom)]
+use loom::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(not(loom))]
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use {Named, NamedVec};
+
+const DEFAULT_SHARDS: usize = 16;
+
+type Entry<T> = Arc<RwLock<T>>;
+
+/// Error returned by [`ConcurrentNamedVec::update()`](struct.ConcurrentNamedVec.html#method.update)
+/// when the function changes the element's name.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RenameError {
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "update() renamed element {:?} to {:?}", self.expected, self.found)
+    }
+}
+
+impl Error for RenameError {}
+
+/// `NamedVec` that can be shared between threads.
+///
+/// The name index is split into shards, each behind its own `RwLock`, so reads by name
+/// only contend with writes that touch the same shard. Each element has its own lock as well,
+/// which lets [`update()`](#method.update) modify one element without blocking readers of others.
+///
+/// Elements keep the order in which they were first inserted, just like
+/// [`NamedVec::push()`](struct.NamedVec.html#method.push).
+///
+/// # Panics
+///
+/// All methods panic if an internal lock has been poisoned by a panicking thread. This cannot
+/// be caused by a closure passed to [`update()`](#method.update), since it only ever sees a copy
+/// of the element.
+pub struct ConcurrentNamedVec<T: Named> {
+    shards: Vec<RwLock<HashMap<String, Entry<T>>>>,
+    // Every element in insertion order. Writers that change the set of names lock this first.
+    order: Mutex<Vec<Entry<T>>>,
+}
+
+impl<T: Named> ConcurrentNamedVec<T> {
+    /// Creates an empty `ConcurrentNamedVec<T>`.
+    pub fn new() -> Self {
+        ConcurrentNamedVec::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Creates an empty `ConcurrentNamedVec<T>` whose name index is split into `shards` shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards > 0, "a ConcurrentNamedVec needs at least one shard");
+
+        ConcurrentNamedVec {
+            shards: (0..shards).map(|_| RwLock::new(HashMap::new())).collect(),
+            order: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.order.lock().unwrap().len()
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the vector contains an element named `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.shard(name).read().unwrap().contains_key(name)
+    }
+
+    /// Returns a clone of the element named `name`, or `None` if there isn't one.
+    pub fn get_cloned(&self, name: &str) -> Option<T> where T: Clone {
+        self.entry(name).map(|entry| read(&entry).clone())
+    }
+
+    /// Calls `f` with a mutable reference to a copy of the element named `name`, and replaces
+    /// the element with the copy if its name is unchanged.
+    ///
+    /// Returns the result of `f`, or `Ok(None)` if there is no such element. If `f` changes
+    /// the name, the element is left as it was and an error is returned.
+    pub fn update<F, R>(&self, name: &str, f: F) -> Result<Option<R>, RenameError>
+    where T: Clone, F: FnOnce(&mut T) -> R {
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        let mut item = write(&entry);
+        let mut updated = item.clone();
+        let result = f(&mut updated);
+        if updated.name() != name {
+            return Err(RenameError { expected: name.to_owned(), found: updated.name().to_owned() });
+        }
+        *item = updated;
+        Ok(Some(result))
+    }
+
+    /// Appends an element to the back of the collection,
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// Returns the replaced element, if any.
+    pub fn upsert(&self, value: T) -> Option<T> {
+        let mut order = self.order.lock().unwrap();
+        let mut shard = self.shard(value.name()).write().unwrap();
+
+        if let Some(entry) = shard.get(value.name()) {
+            let mut item = write(entry);
+            return Some(std::mem::replace(&mut *item, value));
+        }
+
+        let entry = Arc::new(RwLock::new(value));
+        shard.insert(entry.read().unwrap().name().to_owned(), entry.clone());
+        order.push(entry);
+        None
+    }
+
+    /// Removes the element named `name` and returns a clone of it,
+    /// or returns `None` if there is no such element.
+    ///
+    /// Note that removing an element requires searching the insertion order,
+    /// which is a linear operation.
+    pub fn remove(&self, name: &str) -> Option<T> where T: Clone {
+        let mut order = self.order.lock().unwrap();
+        let entry = self.shard(name).write().unwrap().remove(name)?;
+
+        order.retain(|e| !Arc::ptr_eq(e, &entry));
+        let item = read(&entry).clone();
+        Some(item)
+    }
+
+    /// Returns a point-in-time copy of every element, in order.
+    ///
+    /// No element can be inserted or removed while the snapshot is taken,
+    /// but elements are read one at a time, so concurrent calls to
+    /// [`update()`](#method.update) may be reflected for some elements and not others.
+    pub fn snapshot(&self) -> NamedVec<T> where T: Clone {
+        let order = self.order.lock().unwrap();
+        let mut named_vec = NamedVec::with_capacity(order.len());
+        for entry in order.iter() {
+            named_vec.push(read(entry).clone());
+        }
+        named_vec
+    }
+
+    fn entry(&self, name: &str) -> Option<Entry<T>> {
+        self.shard(name).read().unwrap().get(name).cloned()
+    }
+
+    fn shard(&self, name: &str) -> &RwLock<HashMap<String, Entry<T>>> {
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+}
+
+// A panic in `update()` can poison an element's lock, but never while the element is
+// half-written, so the lock can still be used
+fn read<T>(entry: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    entry.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write<T>(entry: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    entry.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<T: Named> Default for ConcurrentNamedVec<T> {
+    fn default() -> Self {
+        ConcurrentNamedVec::new()
+    }
+}
+
+impl<T: Named> From<NamedVec<T>> for ConcurrentNamedVec<T> {
+    fn from(named_vec: NamedVec<T>) -> Self {
+        let concurrent = ConcurrentNamedVec::new();
+        for item in named_vec {
+            concurrent.upsert(item);
+        }
+        concurrent
+    }
+}
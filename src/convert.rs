@@ -0,0 +1,131 @@
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter::FromIterator;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::hash::BuildHasher;
+
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+
+use {Named, NamedVec};
+
+/// A map entry whose key differs from its element's name.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct KeyMismatch {
+    pub key: String,
+    pub name: String,
+}
+
+/// Error returned when converting a map into a `NamedVec` in which some elements
+/// are not named by their keys.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FromMapError {
+    /// Every mismatched entry, in the map's iteration order.
+    pub mismatches: Vec<KeyMismatch>,
+}
+
+impl fmt::Display for FromMapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "keys do not match element names:")?;
+        for mismatch in &self.mismatches {
+            write!(f, " {:?} (named {:?})", mismatch.key, mismatch.name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromMapError {}
+
+impl<T: Named> NamedVec<T> {
+    /// Moves the elements into a map from their names, such as a `HashMap<String, T>`.
+    ///
+    /// Entries are inserted in index order, so a map that keeps insertion order
+    /// (like an `IndexMap`) keeps the vector's order.
+    pub fn into_map<M>(self) -> M where M: FromIterator<(String, T)> {
+        self.items.into_iter()
+            .map(|item| (item.name().to_owned(), item))
+            .collect()
+    }
+
+    /// Builds a `NamedVec` from the entries of a map, using `f` to turn each key and value
+    /// into an element.
+    ///
+    /// Elements are kept in the map's iteration order. Any iterator of pairs is accepted;
+    /// if a key repeats, the later entry replaces the earlier one, as when collecting into
+    /// a map. Returns an error listing every entry whose element is not named by its key.
+    pub fn from_map_with_names<M, V, F>(map: M, mut f: F) -> Result<Self, FromMapError>
+    where M: IntoIterator<Item = (String, V)>, F: FnMut(&str, V) -> T {
+        let mut named_vec = NamedVec::new();
+        let mut mismatches = Vec::new();
+        for (key, value) in map {
+            let item = f(&key, value);
+            if item.name() != key {
+                mismatches.push(KeyMismatch { name: item.name().to_owned(), key });
+            }
+            named_vec.push(item);
+        }
+
+        if mismatches.is_empty() {
+            Ok(named_vec)
+        } else {
+            Err(FromMapError { mismatches })
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Named, S: BuildHasher + Default> From<NamedVec<T>> for HashMap<String, T, S> {
+    fn from(named_vec: NamedVec<T>) -> Self {
+        named_vec.into_map()
+    }
+}
+
+/// Elements are in an arbitrary order.
+#[cfg(feature = "std")]
+impl<T: Named, S: BuildHasher> TryFrom<HashMap<String, T, S>> for NamedVec<T> {
+    type Error = FromMapError;
+
+    fn try_from(map: HashMap<String, T, S>) -> Result<Self, FromMapError> {
+        NamedVec::from_map_with_names(map, |_, item| item)
+    }
+}
+
+impl<T: Named> From<NamedVec<T>> for BTreeMap<String, T> {
+    fn from(named_vec: NamedVec<T>) -> Self {
+        named_vec.into_map()
+    }
+}
+
+/// Elements are sorted by name.
+impl<T: Named> TryFrom<BTreeMap<String, T>> for NamedVec<T> {
+    type Error = FromMapError;
+
+    fn try_from(map: BTreeMap<String, T>) -> Result<Self, FromMapError> {
+        NamedVec::from_map_with_names(map, |_, item| item)
+    }
+}
+
+/// Entries are in index order.
+#[cfg(feature = "indexmap")]
+impl<T: Named, S: BuildHasher + Default> From<NamedVec<T>> for IndexMap<String, T, S> {
+    fn from(named_vec: NamedVec<T>) -> Self {
+        named_vec.into_map()
+    }
+}
+
+/// Elements are in the map's order.
+#[cfg(feature = "indexmap")]
+impl<T: Named, S: BuildHasher> TryFrom<IndexMap<String, T, S>> for NamedVec<T> {
+    type Error = FromMapError;
+
+    fn try_from(map: IndexMap<String, T, S>) -> Result<Self, FromMapError> {
+        NamedVec::from_map_with_names(map, |_, item| item)
+    }
+}
@@ -0,0 +1,149 @@
+use alloc::sync::Arc;
+use core::fmt;
+use core::ops::Deref;
+
+use {Lookup, Named, NamedVec};
+
+/// `NamedVec` whose clones share their elements until one of them is changed.
+///
+/// Cloning a `CowNamedVec<T>` only increments a reference count. The first change made
+/// through a clone whose storage is shared copies the elements and name index, after which
+/// that clone has its own storage (see [`make_mut()`](#method.make_mut)).
+///
+/// `CowNamedVec<T>` dereferences to `NamedVec<T>` for reading.
+#[derive(Clone)]
+pub struct CowNamedVec<T: Named> {
+    named_vec: Arc<NamedVec<T>>,
+}
+
+impl<T: Named> CowNamedVec<T> {
+    /// Creates an empty `CowNamedVec<T>`.
+    pub fn new() -> Self {
+        CowNamedVec::from(NamedVec::new())
+    }
+
+    /// Returns `true` if other clones currently share this vector's storage,
+    /// so that the next change will copy it.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.named_vec) > 1
+    }
+
+    /// Returns `true` if both vectors share the same storage.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.named_vec, &other.named_vec)
+    }
+}
+
+impl<T: Named + Clone> CowNamedVec<T> {
+    /// Returns a mutable reference to the underlying `NamedVec<T>`,
+    /// first copying it if the storage is shared.
+    pub fn make_mut(&mut self) -> &mut NamedVec<T> {
+        Arc::make_mut(&mut self.named_vec)
+    }
+
+    /// Returns the underlying `NamedVec<T>`, copying it if the storage is shared.
+    pub fn into_inner(self) -> NamedVec<T> {
+        Arc::try_unwrap(self.named_vec).unwrap_or_else(|shared| (*shared).clone())
+    }
+
+    /// Appends an element to the back of the collection,
+    /// or replaces an element with the same name if one exists.
+    pub fn push(&mut self, value: T) {
+        self.make_mut().push(value);
+    }
+
+    /// Inserts an element at position `index` (shifting all elements after it to the right),
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.make_mut().insert(index, value);
+    }
+
+    /// Removes and returns the element specified by `lookup`.
+    ///
+    /// See [`NamedVec::remove()`](struct.NamedVec.html#method.remove) for more information.
+    pub fn remove<'a, A>(&mut self, lookup: A) -> T where A: 'a + Into<Lookup<'a>> + Copy {
+        self.make_mut().remove(lookup)
+    }
+
+    /// Swaps two elements.
+    ///
+    /// See [`NamedVec::swap()`](struct.NamedVec.html#method.swap) for more information.
+    pub fn swap<'a, 'b, A, B>(&mut self, first: A, second: B)
+    where A: 'a + Into<Lookup<'a>> + Copy, B: 'b + Into<Lookup<'b>> + Copy {
+        self.make_mut().swap(first, second);
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the rest.
+    ///
+    /// Shared storage is not copied if this has no effect.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.make_mut().truncate(len);
+        }
+    }
+
+    /// Removes the last element from the vector and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.make_mut().pop()
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    ///
+    /// Instead of copying shared storage, this gives the vector new, empty storage.
+    pub fn clear(&mut self) {
+        if self.is_shared() {
+            self.named_vec = Arc::new(NamedVec::with_index_threshold(self.index_threshold()));
+        } else {
+            self.make_mut().clear();
+        }
+    }
+
+    /// Returns a mutable reference to an element, first copying the storage if it is shared.
+    ///
+    /// See [`NamedVec::get()`](struct.NamedVec.html#method.get) for more information.
+    pub fn get_mut<'a, A>(&mut self, lookup: A) -> Option<&mut T> where A: 'a + Into<Lookup<'a>> {
+        self.make_mut().get_mut(lookup)
+    }
+}
+
+impl<T: Named> Default for CowNamedVec<T> {
+    fn default() -> Self {
+        CowNamedVec::new()
+    }
+}
+
+impl<T: Named> From<NamedVec<T>> for CowNamedVec<T> {
+    fn from(named_vec: NamedVec<T>) -> Self {
+        CowNamedVec {
+            named_vec: Arc::new(named_vec),
+        }
+    }
+}
+
+impl<T: Named> Deref for CowNamedVec<T> {
+    type Target = NamedVec<T>;
+
+    fn deref(&self) -> &NamedVec<T> {
+        &self.named_vec
+    }
+}
+
+impl<T: Named + fmt::Debug> fmt::Debug for CowNamedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.named_vec.fmt(f)
+    }
+}
+
+impl<T: Named + PartialEq> PartialEq for CowNamedVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.named_vec == other.named_vec
+    }
+}
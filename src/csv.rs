@@ -0,0 +1,287 @@
+//! Reading and writing `NamedVec`s as CSV.
+//!
+//! The first row of the input is a header naming each column. One column holds each element's
+//! name; the rest are handed to a caller-supplied function that builds the element.
+//! Fields may be quoted with `"`, in which case they can contain commas, line breaks and
+//! doubled `""` quotes.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::Peekable;
+use core::mem;
+use core::str::Chars;
+
+use {Map, Named, NamedVec};
+
+/// A name that appears more than once in the input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Duplicate {
+    pub name: String,
+    /// Line of the row where the name first appeared.
+    pub first_line: usize,
+    /// Line of the row that repeats it.
+    pub line: usize,
+}
+
+/// Error returned by [`read()`](fn.read.html).
+#[derive(Debug, PartialEq, Clone)]
+pub enum CsvError<E> {
+    /// The input is not valid CSV.
+    Syntax { line: usize, message: &'static str },
+    /// The header has no column with the requested name.
+    MissingColumn(String),
+    /// A row has a different number of fields than the header.
+    FieldCount { line: usize, expected: usize, found: usize },
+    /// The caller's function failed to build an element.
+    Record { line: usize, error: E },
+    /// The caller's function built an element whose name isn't the one in the name column.
+    NameMismatch { line: usize, expected: String, found: String },
+    /// Some names appear more than once.
+    Duplicates(Vec<Duplicate>),
+}
+
+impl<E: fmt::Display> fmt::Display for CsvError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CsvError::Syntax { line, message } => {
+                write!(f, "line {}: {}", line, message)
+            },
+            CsvError::MissingColumn(ref column) => {
+                write!(f, "no column named {:?}", column)
+            },
+            CsvError::FieldCount { line, expected, found } => {
+                write!(f, "line {}: expected {} fields, found {}", line, expected, found)
+            },
+            CsvError::Record { line, ref error } => {
+                write!(f, "line {}: {}", line, error)
+            },
+            CsvError::NameMismatch { line, ref expected, ref found } => {
+                write!(f, "line {}: expected an element named {:?}, got {:?}", line, expected, found)
+            },
+            CsvError::Duplicates(ref duplicates) => {
+                write!(f, "duplicate names:")?;
+                for duplicate in duplicates {
+                    write!(f, " {:?} (lines {} and {})",
+                           duplicate.name, duplicate.first_line, duplicate.line)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + fmt::Display> std::error::Error for CsvError<E> {}
+
+/// A row of CSV input.
+pub struct Record<'a> {
+    headers: &'a [String],
+    fields: &'a [String],
+    name_column: usize,
+    line: usize,
+}
+
+impl<'a> Record<'a> {
+    /// Returns the value of the name column.
+    pub fn name(&self) -> &'a str {
+        &self.fields[self.name_column]
+    }
+
+    /// Returns the field in the column named `column`, or `None` if there is no such column.
+    pub fn get(&self, column: &str) -> Option<&'a str> {
+        self.headers.iter()
+            .position(|header| header == column)
+            .map(|i| self.fields[i].as_str())
+    }
+
+    /// Returns every field in column order.
+    pub fn fields(&self) -> &'a [String] {
+        self.fields
+    }
+
+    /// Returns the line of the input on which this row starts.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+/// Builds a `NamedVec<T>` from CSV `input`, using `parse` to turn each row into an element.
+///
+/// Elements are named by the column whose header is `name_column`. Rows are kept in input
+/// order. If any name appears more than once, every repeat is reported in
+/// [`CsvError::Duplicates`](enum.CsvError.html#variant.Duplicates).
+pub fn read<T, E, F>(input: &str, name_column: &str, mut parse: F)
+    -> Result<NamedVec<T>, CsvError<E>>
+where T: Named, F: FnMut(&Record) -> Result<T, E> {
+    let mut rows = parse_rows(input)
+        .map_err(|(line, message)| CsvError::Syntax { line, message })?
+        .into_iter();
+
+    let (_, headers) = rows.next()
+        .ok_or_else(|| CsvError::MissingColumn(name_column.to_owned()))?;
+    let name_index = headers.iter()
+        .position(|header| header == name_column)
+        .ok_or_else(|| CsvError::MissingColumn(name_column.to_owned()))?;
+
+    let mut named_vec = NamedVec::new();
+    let mut lines = Map::new();
+    let mut duplicates = Vec::new();
+
+    for (line, fields) in rows {
+        if fields.len() != headers.len() {
+            return Err(CsvError::FieldCount { line, expected: headers.len(), found: fields.len() });
+        }
+
+        let name = &fields[name_index];
+        if let Some(&first_line) = lines.get(name) {
+            duplicates.push(Duplicate { name: name.clone(), first_line, line });
+            continue;
+        }
+        lines.insert(name.clone(), line);
+
+        let record = Record {
+            headers: &headers,
+            fields: &fields,
+            name_column: name_index,
+            line,
+        };
+        let item = parse(&record).map_err(|error| CsvError::Record { line, error })?;
+        if item.name() != name {
+            return Err(CsvError::NameMismatch {
+                line,
+                expected: name.clone(),
+                found: item.name().to_owned(),
+            });
+        }
+        named_vec.push(item);
+    }
+
+    if duplicates.is_empty() {
+        Ok(named_vec)
+    } else {
+        Err(CsvError::Duplicates(duplicates))
+    }
+}
+
+/// Writes `named_vec` to `out` as CSV, in index order.
+///
+/// The first row holds `columns`; each following row holds the fields returned by `fields`
+/// for one element. Fields are quoted when needed. Rows end with `\n`.
+pub fn write<T, W, F>(out: &mut W, named_vec: &NamedVec<T>, columns: &[&str], mut fields: F)
+    -> fmt::Result
+where T: Named, W: fmt::Write, F: FnMut(&T) -> Vec<String> {
+    write_row(out, columns.iter().cloned())?;
+    for item in named_vec {
+        write_row(out, fields(item).iter().map(String::as_str))?;
+    }
+    Ok(())
+}
+
+fn write_row<'a, W, I>(out: &mut W, fields: I) -> fmt::Result
+where W: fmt::Write, I: Iterator<Item = &'a str> {
+    let mut fields = fields.peekable();
+    let mut first = true;
+    while let Some(field) = fields.next() {
+        if !first {
+            out.write_char(',')?;
+        }
+        // A row with a single empty field would otherwise be a blank line, which is skipped
+        let only_empty = first && field.is_empty() && fields.peek().is_none();
+        first = false;
+        if only_empty || field.contains(&[',', '"', '\n', '\r'][..]) {
+            write!(out, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            out.write_str(field)?;
+        }
+    }
+    out.write_char('\n')
+}
+
+// A row's starting line and its fields
+type Row = (usize, Vec<String>);
+
+// Splits `input` into rows. Blank lines are skipped, but a line holding only `""` is a row
+// with one empty field.
+fn parse_rows(input: &str) -> Result<Vec<Row>, (usize, &'static str)> {
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+    let mut rows = Vec::new();
+
+    while chars.peek().is_some() {
+        let start = line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+
+        loop {
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                quoted = true;
+                parse_quoted(&mut chars, &mut field, &mut line)
+                    .map_err(|message| (start, message))?;
+            } else {
+                while let Some(&c) = chars.peek() {
+                    match c {
+                        ',' | '\r' | '\n' => break,
+                        '"' => return Err((line, "quote in unquoted field")),
+                        _ => field.push(c),
+                    }
+                    chars.next();
+                }
+            }
+            fields.push(mem::take(&mut field));
+
+            match chars.next() {
+                Some(',') => continue,
+                Some('\r') => {
+                    if chars.peek() == Some(&'\n') {
+                        chars.next();
+                    }
+                    line += 1;
+                },
+                Some('\n') => {
+                    line += 1;
+                },
+                _ => {},
+            }
+            break;
+        }
+
+        if fields.len() > 1 || !fields[0].is_empty() || quoted {
+            rows.push((start, fields));
+        }
+    }
+
+    Ok(rows)
+}
+
+// Reads the rest of a quoted field, after its opening quote
+fn parse_quoted(chars: &mut Peekable<Chars>, field: &mut String, line: &mut usize)
+    -> Result<(), &'static str> {
+    loop {
+        match chars.next() {
+            Some('"') => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    break;
+                }
+            },
+            Some(c) => {
+                if c == '\n' {
+                    *line += 1;
+                }
+                field.push(c);
+            },
+            None => return Err("unterminated quoted field"),
+        }
+    }
+
+    match chars.peek() {
+        Some(&',') | Some(&'\r') | Some(&'\n') | None => Ok(()),
+        Some(_) => Err("unexpected character after quoted field"),
+    }
+}
@@ -0,0 +1,241 @@
+use alloc::borrow::ToOwned;
+use alloc::collections::vec_deque::{self, VecDeque};
+use core::fmt;
+use core::ops::Index;
+
+use {map_with_capacity, Lookup, Map, Named, NamedVec};
+
+/// Double-ended queue where each element has an associated name.
+///
+/// `NamedVecDeque` mirrors [`NamedVec`](struct.NamedVec.html)'s API, but keeps its elements
+/// in a `VecDeque`, so elements can be added and removed at both ends in O(1).
+///
+/// The name index stores a logical position for each element rather than its index:
+/// the element at index `i` has position `front + i`, where `front` moves when elements are
+/// pushed or popped at the front. Pushing or popping at either end therefore never has to
+/// update the positions of other elements.
+///
+/// `Handle`s are not supported; looking up a `Handle` always returns `None`.
+#[derive(Clone)]
+pub struct NamedVecDeque<T: Named> {
+    items: VecDeque<T>,
+    // Position of each name, which wraps around `usize`
+    map: Map,
+    // Position of the front element
+    front: usize,
+}
+
+impl<T: Named> NamedVecDeque<T> {
+    /// Creates an empty `NamedVecDeque<T>`.
+    pub fn new() -> Self {
+        NamedVecDeque::with_capacity(0)
+    }
+
+    /// Creates an empty `NamedVecDeque<T>` with space for at least `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        NamedVecDeque {
+            items: VecDeque::with_capacity(capacity),
+            map: map_with_capacity(capacity),
+            front: 0,
+        }
+    }
+
+    /// Returns the number of elements in the deque.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the deque contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Appends an element to the back of the deque,
+    /// or replaces an element with the same name if one exists.
+    pub fn push_back(&mut self, value: T) {
+        if let Some(i) = self.position(value.name()) {
+            self.items[i] = value;
+            return;
+        }
+
+        let position = self.front.wrapping_add(self.items.len());
+        self.map.insert(value.name().to_owned(), position);
+        self.items.push_back(value);
+    }
+
+    /// Prepends an element to the front of the deque,
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// A replaced element keeps its index.
+    pub fn push_front(&mut self, value: T) {
+        if let Some(i) = self.position(value.name()) {
+            self.items[i] = value;
+            return;
+        }
+
+        self.front = self.front.wrapping_sub(1);
+        self.map.insert(value.name().to_owned(), self.front);
+        self.items.push_front(value);
+    }
+
+    /// Removes the last element and returns it, or `None` if the deque is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        let item = self.items.pop_back()?;
+        self.map.remove(item.name());
+        Some(item)
+    }
+
+    /// Removes the first element and returns it, or `None` if the deque is empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        let item = self.items.pop_front()?;
+        self.map.remove(item.name());
+        self.front = self.front.wrapping_add(1);
+        Some(item)
+    }
+
+    /// Returns a reference to the first element, or `None` if the deque is empty.
+    pub fn front(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    /// Returns a reference to the last element, or `None` if the deque is empty.
+    pub fn back(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    /// Removes and returns the element specified by `lookup`.
+    ///
+    /// Whichever of the elements before or after it are fewer are shifted to fill the gap.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if a `usize` argument is out of bounds.
+    /// * Panics if a `&str` argument is an invalid name.
+    /// * Panics if given a `Handle`.
+    pub fn remove<'a, A>(&mut self, lookup: A) -> T where A: 'a + Into<Lookup<'a>> {
+        let index = self.index_from_lookup(lookup.into()).expect("invalid lookup");
+        let item = self.items.remove(index).unwrap();
+        self.map.remove(item.name());
+
+        if index < self.items.len() - index {
+            for item in self.items.range(..index) {
+                let position = self.map.get_mut(item.name()).unwrap();
+                *position = position.wrapping_add(1);
+            }
+            self.front = self.front.wrapping_add(1);
+        } else {
+            for item in self.items.range(index..) {
+                let position = self.map.get_mut(item.name()).unwrap();
+                *position = position.wrapping_sub(1);
+            }
+        }
+        item
+    }
+
+    /// Clears the deque, removing all values.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.map.clear();
+        self.front = 0;
+    }
+
+    /// Returns a reference to an element.
+    ///
+    /// See [`NamedVec::get()`](struct.NamedVec.html#method.get) for more information.
+    pub fn get<'a, A>(&self, lookup: A) -> Option<&T> where A: 'a + Into<Lookup<'a>> {
+        self.index_from_lookup(lookup.into()).and_then(|i| self.items.get(i))
+    }
+
+    /// Returns a mutable reference to an element.
+    ///
+    /// See [`NamedVec::get()`](struct.NamedVec.html#method.get) for more information.
+    pub fn get_mut<'a, A>(&mut self, lookup: A) -> Option<&mut T> where A: 'a + Into<Lookup<'a>> {
+        self.index_from_lookup(lookup.into()).and_then(move |i| self.items.get_mut(i))
+    }
+
+    /// Returns an iterator over the elements from front to back.
+    pub fn iter(&self) -> vec_deque::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    fn index_from_lookup(&self, lookup: Lookup) -> Option<usize> {
+        match lookup {
+            Lookup::Name(name) => self.position(name),
+            Lookup::Index(index) => Some(index).filter(|&i| i < self.items.len()),
+            Lookup::Handle(_) => None,
+        }
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.map.get(name).map(|position| position.wrapping_sub(self.front))
+    }
+}
+
+impl<T: Named> Default for NamedVecDeque<T> {
+    fn default() -> Self {
+        NamedVecDeque::new()
+    }
+}
+
+impl<T: Named> From<NamedVec<T>> for NamedVecDeque<T> {
+    fn from(named_vec: NamedVec<T>) -> Self {
+        let mut deque = NamedVecDeque::with_capacity(named_vec.len());
+        for item in named_vec {
+            deque.push_back(item);
+        }
+        deque
+    }
+}
+
+/// Formats the elements as a map from names to elements, from front to back.
+impl<T: Named + fmt::Debug> fmt::Debug for NamedVecDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.items.iter().map(|item| (item.name(), item)))
+            .finish()
+    }
+}
+
+impl<T: Named + PartialEq> PartialEq for NamedVecDeque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<T: Named> Index<usize> for NamedVecDeque<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T: Named> Index<&str> for NamedVecDeque<T> {
+    type Output = T;
+
+    fn index(&self, index: &str) -> &T {
+        self.get(index).unwrap()
+    }
+}
+
+///////////////
+// Iterators //
+///////////////
+
+impl<'a, T: Named> IntoIterator for &'a NamedVecDeque<T> {
+    type Item = &'a T;
+    type IntoIter = vec_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> vec_deque::Iter<'a, T> {
+        self.items.iter()
+    }
+}
+
+impl<T: Named> IntoIterator for NamedVecDeque<T> {
+    type Item = T;
+    type IntoIter = vec_deque::IntoIter<T>;
+
+    fn into_iter(self) -> vec_deque::IntoIter<T> {
+        self.items.into_iter()
+    }
+}
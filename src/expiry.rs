@@ -0,0 +1,247 @@
+//! `NamedVec` whose elements can expire.
+
+use std::convert::TryFrom;
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use {Lookup, Named, NamedVec};
+
+/// Source of the current time for [`Expiring`](struct.Expiring.html).
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// `Clock` that reads the system's monotonic clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// `Clock` that only moves when told to, for tests.
+///
+/// Clones share the same time, so a test can keep one to
+/// [`advance()`](#method.advance) while an `Expiring` owns another.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed_nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Creates a `ManualClock` that starts at the current time.
+    pub fn new() -> Self {
+        ManualClock {
+            start: Instant::now(),
+            elapsed_nanos: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    ///
+    /// The clock stops at about 584 years after it was created.
+    pub fn advance(&self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        let _ = self.elapsed_nanos.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |elapsed| {
+            Some(elapsed.saturating_add(nanos))
+        });
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + Duration::from_nanos(self.elapsed_nanos.load(Ordering::SeqCst))
+    }
+}
+
+/// `NamedVec` whose elements can be given a time to live.
+///
+/// An element pushed with [`push_with_ttl()`](#method.push_with_ttl) expires once its
+/// deadline has passed. Expired elements are treated as missing by lookups, but are only
+/// removed by [`purge_expired()`](#method.purge_expired).
+#[derive(Debug, Clone)]
+pub struct Expiring<T: Named, C: Clock = SystemClock> {
+    named_vec: NamedVec<T>,
+    // Deadline of each element in `named_vec`, kept in the same order
+    deadlines: Vec<Option<Instant>>,
+    clock: C,
+}
+
+impl<T: Named> Expiring<T> {
+    /// Creates an empty `Expiring<T>` that uses the system clock.
+    pub fn new() -> Self {
+        Expiring::with_clock(SystemClock)
+    }
+}
+
+impl<T: Named, C: Clock> Expiring<T, C> {
+    /// Creates an empty `Expiring<T, C>` that reads the time from `clock`.
+    pub fn with_clock(clock: C) -> Self {
+        Expiring {
+            named_vec: NamedVec::new(),
+            deadlines: Vec::new(),
+            clock,
+        }
+    }
+
+    /// Returns the number of elements, including expired elements that have not been purged.
+    pub fn len(&self) -> usize {
+        self.named_vec.len()
+    }
+
+    /// Returns `true` if there are no elements, including expired ones.
+    pub fn is_empty(&self) -> bool {
+        self.named_vec.is_empty()
+    }
+
+    /// Appends an element that never expires,
+    /// or replaces an element with the same name if one exists.
+    pub fn push(&mut self, value: T) {
+        self.push_with_deadline(value, None);
+    }
+
+    /// Appends an element that expires after `ttl`,
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// If the deadline is too far in the future to be represented (for example, with a `ttl`
+    /// of `Duration::MAX`), the element never expires.
+    pub fn push_with_ttl(&mut self, value: T, ttl: Duration) {
+        let deadline = self.clock.now().checked_add(ttl);
+        self.push_with_deadline(value, deadline);
+    }
+
+    /// Returns a reference to an element, or `None` if it doesn't exist or has expired.
+    ///
+    /// See [`NamedVec::get()`](../struct.NamedVec.html#method.get) for more information.
+    pub fn get<'a, A>(&self, lookup: A) -> Option<&T> where A: 'a + Into<Lookup<'a>> {
+        let index = self.live_index(lookup.into())?;
+        self.named_vec.items.get(index)
+    }
+
+    /// Returns a mutable reference to an element, or `None` if it doesn't exist or
+    /// has expired.
+    ///
+    /// See [`NamedVec::get()`](../struct.NamedVec.html#method.get) for more information.
+    pub fn get_mut<'a, A>(&mut self, lookup: A) -> Option<&mut T> where A: 'a + Into<Lookup<'a>> {
+        let index = self.live_index(lookup.into())?;
+        self.named_vec.items.get_mut(index)
+    }
+
+    /// Returns the deadline of an element, or `None` if it doesn't exist or never expires.
+    pub fn deadline<'a, A>(&self, lookup: A) -> Option<Instant> where A: 'a + Into<Lookup<'a>> {
+        let index = self.named_vec.index_from_lookup(lookup.into())?;
+        *self.deadlines.get(index)?
+    }
+
+    /// Removes and returns an element, whether or not it has expired.
+    ///
+    /// See [`NamedVec::remove()`](../struct.NamedVec.html#method.remove) for more information.
+    pub fn remove<'a, A>(&mut self, lookup: A) -> T where A: 'a + Into<Lookup<'a>> {
+        let index = self.named_vec.index_from_lookup(lookup.into()).expect("invalid lookup");
+        self.deadlines.remove(index);
+        self.named_vec.remove(index)
+    }
+
+    /// Removes every element whose deadline is at or before `now`, returning them in order.
+    ///
+    /// This takes a single pass over the elements and rebuilds the name index at most once.
+    pub fn purge_expired(&mut self, now: Instant) -> Vec<T> {
+        let keep: Vec<bool> = self.deadlines.iter()
+            .map(|&deadline| is_live(deadline, now))
+            .collect();
+        if keep.iter().all(|&keep| keep) {
+            return Vec::new();
+        }
+
+        self.deadlines.retain(|&deadline| is_live(deadline, now));
+        self.named_vec.retain_indices(&keep)
+    }
+
+    /// Clears the collection, removing all values.
+    pub fn clear(&mut self) {
+        self.named_vec.clear();
+        self.deadlines.clear();
+    }
+
+    /// Returns an iterator over the elements that have not expired, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let now = self.clock.now();
+        self.named_vec.items.iter()
+            .zip(&self.deadlines)
+            .filter(move |&(_, &deadline)| is_live(deadline, now))
+            .map(|(item, _)| item)
+    }
+
+    /// Returns the underlying `NamedVec<T>`, including expired elements.
+    pub fn into_inner(self) -> NamedVec<T> {
+        self.named_vec
+    }
+
+    fn push_with_deadline(&mut self, value: T, deadline: Option<Instant>) {
+        match self.named_vec.position(value.name()) {
+            Some(i) => self.deadlines[i] = deadline,
+            None => self.deadlines.push(deadline),
+        }
+        self.named_vec.push(value);
+    }
+
+    fn live_index(&self, lookup: Lookup) -> Option<usize> {
+        let index = self.named_vec.index_from_lookup(lookup)?;
+        let deadline = *self.deadlines.get(index)?;
+        Some(index).filter(|_| is_live(deadline, self.clock.now()))
+    }
+}
+
+fn is_live(deadline: Option<Instant>, now: Instant) -> bool {
+    match deadline {
+        Some(deadline) => deadline > now,
+        None => true,
+    }
+}
+
+impl<T: Named> Default for Expiring<T> {
+    fn default() -> Self {
+        Expiring::new()
+    }
+}
+
+impl<T: Named> NamedVec<T> {
+    // Removes the elements whose entry in `keep` is false, returning them in order
+    fn retain_indices(&mut self, keep: &[bool]) -> Vec<T> {
+        let mut removed = Vec::new();
+        let mut kept = Vec::with_capacity(self.items.len());
+        let mut kept_slots = Vec::with_capacity(self.items.len());
+        let items = mem::take(&mut self.items);
+        let item_slots = mem::take(&mut self.item_slots);
+
+        for ((item, slot), &keep) in items.into_iter().zip(item_slots).zip(keep) {
+            if keep {
+                kept.push(item);
+                kept_slots.push(slot);
+            } else {
+                removed.push(item);
+                self.free_slot(slot);
+            }
+        }
+
+        self.items = kept;
+        self.item_slots = kept_slots;
+        self.reindex_slots(0);
+        if self.map.is_some() {
+            self.build_index();
+        }
+        self.debug_check_invariants();
+        removed
+    }
+}
@@ -0,0 +1,64 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+
+use {Named, NamedVec};
+
+impl<T: Named> NamedVec<T> {
+    /// Groups references to the elements by the name that `key` returns for each one.
+    ///
+    /// Groups are ordered by where their first member appears, and the members of each group
+    /// keep their order.
+    pub fn group_by<'a, F, S>(&'a self, mut key: F) -> NamedVec<Group<&'a T>>
+    where F: FnMut(&'a T) -> S, S: AsRef<str> {
+        group(self.items.iter(), |&item: &&'a T| key(item))
+    }
+
+    /// Like [`group_by()`](#method.group_by), but moves the elements into the groups.
+    pub fn into_group_by<F, S>(self, key: F) -> NamedVec<Group<T>>
+    where F: FnMut(&T) -> S, S: AsRef<str> {
+        group(self.items, key)
+    }
+}
+
+fn group<T, I, F, S>(items: I, mut key: F) -> NamedVec<Group<T>>
+where T: Named, I: IntoIterator<Item = T>, F: FnMut(&T) -> S, S: AsRef<str> {
+    let mut groups: NamedVec<Group<T>> = NamedVec::new();
+    for item in items {
+        let key = key(&item);
+        match groups.position(key.as_ref()) {
+            Some(i) => groups.items[i].members.push(item),
+            None => {
+                let mut members = NamedVec::new();
+                members.push(item);
+                groups.push(Group { name: key.as_ref().to_owned(), members });
+            },
+        }
+    }
+    groups
+}
+
+/// Elements that share a group name, as returned by
+/// [`NamedVec::group_by()`](struct.NamedVec.html#method.group_by).
+#[derive(Debug, PartialEq, Clone)]
+pub struct Group<T: Named> {
+    name: String,
+    members: NamedVec<T>,
+}
+
+impl<T: Named> Group<T> {
+    /// Returns the group's members.
+    pub fn members(&self) -> &NamedVec<T> {
+        &self.members
+    }
+
+    /// Returns the group's members, consuming the group.
+    pub fn into_members(self) -> NamedVec<T> {
+        self.members
+    }
+}
+
+impl<T: Named> Named for Group<T> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
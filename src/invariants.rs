@@ -0,0 +1,110 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use {Named, NamedVec};
+
+/// An inconsistency found by [`NamedVec::check_invariants()`](struct.NamedVec.html#method.check_invariants).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InvariantViolation {
+    /// Two elements have the same name.
+    DuplicateName { name: String, first: usize, second: usize },
+    /// The element at `index` is missing from the name index.
+    MissingEntry { name: String, index: usize },
+    /// The name index maps the element at `index` to a different position.
+    WrongIndex { name: String, index: usize, found: usize },
+    /// The name index has an entry that matches no element.
+    ExtraEntry { name: String, index: usize },
+    /// The handle slot of the element at `index` does not point back to it.
+    Slot { index: usize },
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InvariantViolation::DuplicateName { ref name, first, second } => {
+                write!(f, "elements {} and {} are both named {:?}", first, second, name)
+            },
+            InvariantViolation::MissingEntry { ref name, index } => {
+                write!(f, "element {} ({:?}) is not in the name index", index, name)
+            },
+            InvariantViolation::WrongIndex { ref name, index, found } => {
+                write!(f, "element {} ({:?}) is indexed at {}", index, name, found)
+            },
+            InvariantViolation::ExtraEntry { ref name, index } => {
+                write!(f, "name index maps {:?} to {}, which has a different name", name, index)
+            },
+            InvariantViolation::Slot { index } => {
+                write!(f, "handle slot of element {} points elsewhere", index)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantViolation {}
+
+impl<T: Named> NamedVec<T> {
+    /// Checks that the vector's internal state is consistent, returning the first
+    /// problem found.
+    ///
+    /// Names must be unique, and the name index (if it has been built) must map every
+    /// name to its element's position and nothing else. This can fail if elements are
+    /// renamed through [`get_mut()`](#method.get_mut).
+    ///
+    /// With the `debug-invariants` feature, every mutator calls this and panics on failure.
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        order.sort_by(|&a, &b| self.items[a].name().cmp(self.items[b].name()).then(a.cmp(&b)));
+        for pair in order.windows(2) {
+            let name = self.items[pair[0]].name();
+            if name == self.items[pair[1]].name() {
+                return Err(InvariantViolation::DuplicateName {
+                    name: name.into(),
+                    first: pair[0],
+                    second: pair[1],
+                });
+            }
+        }
+
+        if let Some(ref map) = self.map {
+            for (index, item) in self.items.iter().enumerate() {
+                match map.get(item.name()) {
+                    None => {
+                        return Err(InvariantViolation::MissingEntry {
+                            name: item.name().into(),
+                            index,
+                        });
+                    },
+                    Some(&found) if found != index => {
+                        return Err(InvariantViolation::WrongIndex {
+                            name: item.name().into(),
+                            index,
+                            found,
+                        });
+                    },
+                    Some(_) => {},
+                }
+            }
+
+            for (name, &index) in map.iter() {
+                if self.items.get(index).map(Named::name) != Some(name.as_str()) {
+                    return Err(InvariantViolation::ExtraEntry { name: name.clone(), index });
+                }
+            }
+        }
+
+        if self.item_slots.len() != self.items.len() {
+            return Err(InvariantViolation::Slot {
+                index: self.items.len().min(self.item_slots.len()),
+            });
+        }
+        for (index, &slot) in self.item_slots.iter().enumerate() {
+            if self.slots.get(slot).and_then(|slot| slot.index) != Some(index) {
+                return Err(InvariantViolation::Slot { index });
+            }
+        }
+
+        Ok(())
+    }
+}
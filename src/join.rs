@@ -0,0 +1,104 @@
+//! Joining two `NamedVec`s by name.
+
+use {Named, NamedVec};
+
+impl<T: Named> NamedVec<T> {
+    /// Pairs up elements of `self` and `other` that have the same name.
+    ///
+    /// The returned [`Join`](join/struct.Join.html) can iterate over an inner, left outer or
+    /// full outer join. Names are looked up in each vector's name index, so the two vectors
+    /// can hold different element types.
+    ///
+    /// Every join yields elements of `self` in order, followed (for a full outer join) by
+    /// elements found only in `other`, in `other`'s order.
+    pub fn join<'a, U: Named>(&'a self, other: &'a NamedVec<U>) -> Join<'a, T, U> {
+        Join {
+            left: self,
+            right: other,
+        }
+    }
+}
+
+/// Two `NamedVec`s to be joined by name, as returned by
+/// [`NamedVec::join()`](../struct.NamedVec.html#method.join).
+#[derive(Debug)]
+pub struct Join<'a, T: Named + 'a, U: Named + 'a> {
+    left: &'a NamedVec<T>,
+    right: &'a NamedVec<U>,
+}
+
+impl<'a, T: Named, U: Named> Clone for Join<'a, T, U> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: Named, U: Named> Copy for Join<'a, T, U> {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Inner,
+    LeftOuter,
+    FullOuter,
+}
+
+impl<'a, T: Named, U: Named> Join<'a, T, U> {
+    /// Iterates over the names found in both vectors.
+    pub fn inner(self) -> Iter<'a, T, U> {
+        self.iter(Kind::Inner)
+    }
+
+    /// Iterates over the names found in the left vector.
+    pub fn left_outer(self) -> Iter<'a, T, U> {
+        self.iter(Kind::LeftOuter)
+    }
+
+    /// Iterates over the names found in either vector.
+    pub fn full_outer(self) -> Iter<'a, T, U> {
+        self.iter(Kind::FullOuter)
+    }
+
+    fn iter(self, kind: Kind) -> Iter<'a, T, U> {
+        Iter {
+            join: self,
+            kind,
+            index: 0,
+        }
+    }
+}
+
+/// Iterator over a [`Join`](struct.Join.html), yielding each name with the elements
+/// that have it.
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T: Named + 'a, U: Named + 'a> {
+    join: Join<'a, T, U>,
+    kind: Kind,
+    // Index into the left vector, then past its end into the right vector
+    index: usize,
+}
+
+impl<'a, T: Named, U: Named> Iterator for Iter<'a, T, U> {
+    type Item = (&'a str, Option<&'a T>, Option<&'a U>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Join { left, right } = self.join;
+
+        while let Some(item) = left.items.get(self.index) {
+            self.index += 1;
+            let other = right.get(item.name());
+            if other.is_some() || self.kind != Kind::Inner {
+                return Some((item.name(), Some(item), other));
+            }
+        }
+
+        if self.kind == Kind::FullOuter {
+            while let Some(other) = right.items.get(self.index - left.len()) {
+                self.index += 1;
+                if left.position(other.name()).is_none() {
+                    return Some((other.name(), None, Some(other)));
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,331 @@
+//! Undo/redo and change tracking for `NamedVec`.
+
+use alloc::vec::Vec;
+use core::mem;
+use core::ops::Deref;
+
+use {Lookup, Named, NamedVec};
+
+/// A single invertible change to a `NamedVec`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Edit<T> {
+    /// `item` was inserted at `index`.
+    Inserted { index: usize, item: T },
+    /// `item` was removed from `index`.
+    Removed { index: usize, item: T },
+    /// The element at `index` was replaced by one with the same name.
+    Replaced { index: usize, old: T, new: T },
+    /// The elements at the two indices were swapped.
+    Swapped(usize, usize),
+    /// The vector was shortened to `len` elements, dropping `removed`.
+    Truncated { len: usize, removed: Vec<T> },
+    /// `added` was appended to a vector of `len` elements.
+    Extended { len: usize, added: Vec<T> },
+}
+
+impl<T: Clone> Edit<T> {
+    /// Returns the edit that undoes this one.
+    pub fn inverse(&self) -> Edit<T> {
+        match *self {
+            Edit::Inserted { index, ref item } => {
+                Edit::Removed { index, item: item.clone() }
+            },
+            Edit::Removed { index, ref item } => {
+                Edit::Inserted { index, item: item.clone() }
+            },
+            Edit::Replaced { index, ref old, ref new } => {
+                Edit::Replaced { index, old: new.clone(), new: old.clone() }
+            },
+            Edit::Swapped(a, b) => {
+                Edit::Swapped(a, b)
+            },
+            Edit::Truncated { len, ref removed } => {
+                Edit::Extended { len, added: removed.clone() }
+            },
+            Edit::Extended { len, ref added } => {
+                Edit::Truncated { len, removed: added.clone() }
+            },
+        }
+    }
+
+    fn apply(&self, named_vec: &mut NamedVec<T>) where T: Named {
+        match *self {
+            Edit::Inserted { index, ref item } => {
+                named_vec.insert(index, item.clone());
+            },
+            Edit::Removed { index, .. } => {
+                named_vec.remove(index);
+            },
+            Edit::Replaced { index, ref new, .. } => {
+                named_vec.items[index] = new.clone();
+            },
+            Edit::Swapped(a, b) => {
+                named_vec.swap(a, b);
+            },
+            Edit::Truncated { len, .. } => {
+                named_vec.truncate(len);
+            },
+            Edit::Extended { ref added, .. } => {
+                for item in added {
+                    named_vec.push(item.clone());
+                }
+            },
+        }
+    }
+}
+
+/// A position in a [`Journaled`](struct.Journaled.html)'s history.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Checkpoint(usize);
+
+/// `NamedVec` that records every change so that it can be undone and redone.
+///
+/// `Journaled<T>` dereferences to `NamedVec<T>` for reading; all mutation has to go through
+/// its own methods so that it can be recorded. Each call is undone on its own unless it is
+/// made inside [`transaction()`](#method.transaction).
+#[derive(Debug, Clone)]
+pub struct Journaled<T: Named + Clone> {
+    named_vec: NamedVec<T>,
+    // Every edit applied so far, including those made by undo and redo
+    log: Vec<Edit<T>>,
+    undo: Vec<Vec<Edit<T>>>,
+    redo: Vec<Vec<Edit<T>>>,
+    transaction: Option<Vec<Edit<T>>>,
+}
+
+impl<T: Named + Clone> Journaled<T> {
+    /// Creates an empty `Journaled<T>`.
+    pub fn new() -> Self {
+        Journaled::from(NamedVec::new())
+    }
+
+    /// Returns the underlying `NamedVec<T>`, discarding the history.
+    pub fn into_inner(self) -> NamedVec<T> {
+        self.named_vec
+    }
+
+    /// Appends an element to the back of the collection,
+    /// or replaces an element with the same name if one exists.
+    pub fn push(&mut self, value: T) {
+        let index = self.named_vec.len();
+        self.insert_or_replace(index, value);
+    }
+
+    /// Inserts an element at position `index` (shifting all elements after it to the right),
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.insert_or_replace(index, value);
+    }
+
+    /// Removes and returns the element specified by `lookup`.
+    ///
+    /// See [`NamedVec::remove()`](../struct.NamedVec.html#method.remove) for more information.
+    pub fn remove<'a, A>(&mut self, lookup: A) -> T where A: 'a + Into<Lookup<'a>> + Copy {
+        let index = self.named_vec.index_from_lookup(lookup.into()).expect("invalid lookup");
+        let item = self.named_vec.remove(index);
+        self.record(Edit::Removed { index, item: item.clone() });
+        item
+    }
+
+    /// Swaps two elements.
+    ///
+    /// See [`NamedVec::swap()`](../struct.NamedVec.html#method.swap) for more information.
+    pub fn swap<'a, 'b, A, B>(&mut self, first: A, second: B)
+    where A: 'a + Into<Lookup<'a>> + Copy, B: 'b + Into<Lookup<'b>> + Copy {
+        let a = self.named_vec.index_from_lookup(first.into()).expect("invalid lookup");
+        let b = self.named_vec.index_from_lookup(second.into()).expect("invalid lookup");
+        self.named_vec.swap(a, b);
+        if a != b {
+            self.record(Edit::Swapped(a, b));
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no effect.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.named_vec.len() {
+            let removed = self.named_vec.items[len..].to_vec();
+            self.named_vec.truncate(len);
+            self.record(Edit::Truncated { len, removed });
+        }
+    }
+
+    /// Removes the last element from the vector and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let index = self.named_vec.len().checked_sub(1)?;
+        Some(self.remove(index))
+    }
+
+    /// Clears the vector, removing all values.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Groups every change made by `f` so that it is undone and redone as one step.
+    ///
+    /// Nested transactions are merged into the outermost one. If `f` panics, the changes
+    /// made so far in the outermost transaction are rolled back.
+    pub fn transaction<F, R>(&mut self, f: F) -> R where F: FnOnce(&mut Self) -> R {
+        if self.transaction.is_some() {
+            return f(self);
+        }
+
+        self.transaction = Some(Vec::new());
+        let mut guard = TransactionGuard { journaled: self, completed: false };
+        let result = f(guard.journaled);
+        guard.completed = true;
+        result
+    }
+
+    /// Undoes the most recent change or transaction.
+    ///
+    /// Returns `false` if there is nothing to undo.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called inside [`transaction()`](#method.transaction).
+    pub fn undo(&mut self) -> bool {
+        assert!(self.transaction.is_none(), "cannot undo inside a transaction");
+
+        match self.undo.pop() {
+            Some(edits) => {
+                self.revert(&edits);
+                self.redo.push(edits);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Redoes the most recently undone change or transaction.
+    ///
+    /// Returns `false` if there is nothing to redo.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called inside [`transaction()`](#method.transaction).
+    pub fn redo(&mut self) -> bool {
+        assert!(self.transaction.is_none(), "cannot redo inside a transaction");
+
+        match self.redo.pop() {
+            Some(edits) => {
+                for edit in &edits {
+                    edit.apply(&mut self.named_vec);
+                    self.log.push(edit.clone());
+                }
+                self.undo.push(edits);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Returns `true` if there is a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` if there is a change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Returns the current position in the history.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.log.len())
+    }
+
+    /// Returns every edit applied since `checkpoint`, in order.
+    ///
+    /// Undoing and redoing count as changes, so the result always turns the vector as it was at
+    /// `checkpoint` into the vector as it is now.
+    pub fn changes_since(&self, checkpoint: Checkpoint) -> &[Edit<T>] {
+        &self.log[checkpoint.0..]
+    }
+
+    fn insert_or_replace(&mut self, index: usize, value: T) {
+        match self.named_vec.position(value.name()) {
+            Some(i) => {
+                let new = value.clone();
+                let old = mem::replace(&mut self.named_vec.items[i], value);
+                self.record(Edit::Replaced { index: i, old, new });
+            },
+            None => {
+                self.named_vec.insert(index, value.clone());
+                self.record(Edit::Inserted { index, item: value });
+            },
+        }
+    }
+
+    fn revert(&mut self, edits: &[Edit<T>]) {
+        for edit in edits.iter().rev() {
+            let inverse = edit.inverse();
+            inverse.apply(&mut self.named_vec);
+            self.log.push(inverse);
+        }
+    }
+
+    fn record(&mut self, edit: Edit<T>) {
+        self.log.push(edit.clone());
+        // A transaction only clears the redo history once it completes
+        match self.transaction {
+            Some(ref mut edits) => edits.push(edit),
+            None => {
+                self.redo.clear();
+                self.undo.push(alloc::vec![edit]);
+            },
+        }
+    }
+}
+
+// Ends a transaction when dropped, rolling it back if it did not complete
+struct TransactionGuard<'a, T: Named + Clone + 'a> {
+    journaled: &'a mut Journaled<T>,
+    completed: bool,
+}
+
+impl<'a, T: Named + Clone> Drop for TransactionGuard<'a, T> {
+    fn drop(&mut self) {
+        let edits = match self.journaled.transaction.take() {
+            Some(edits) => edits,
+            None => return,
+        };
+        if !self.completed {
+            self.journaled.revert(&edits);
+        } else if !edits.is_empty() {
+            self.journaled.redo.clear();
+            self.journaled.undo.push(edits);
+        }
+    }
+}
+
+impl<T: Named + Clone> Default for Journaled<T> {
+    fn default() -> Self {
+        Journaled::new()
+    }
+}
+
+impl<T: Named + Clone> From<NamedVec<T>> for Journaled<T> {
+    fn from(named_vec: NamedVec<T>) -> Self {
+        Journaled {
+            named_vec,
+            log: Vec::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            transaction: None,
+        }
+    }
+}
+
+impl<T: Named + Clone> Deref for Journaled<T> {
+    type Target = NamedVec<T>;
+
+    fn deref(&self) -> &NamedVec<T> {
+        &self.named_vec
+    }
+}
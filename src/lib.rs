@@ -1,5 +1,133 @@
-use std::collections::hash_map::HashMap;
-use std::ops::{Index, Range, RangeFrom, RangeFull, RangeTo};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate core;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "persistent")]
+extern crate im;
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
+#[cfg(loom)]
+extern crate loom;
+#[cfg(feature = "proptest")]
+extern crate proptest;
+#[cfg(feature = "quickcheck")]
+extern crate quickcheck;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{Index, Range, RangeFrom, RangeFull, RangeTo};
+
+pub mod array;
+pub use array::ArrayNamedVec;
+
+pub mod binary;
+pub use binary::NamedVecRef;
+
+mod convert;
+pub use convert::{FromMapError, KeyMismatch};
+
+mod cow;
+pub use cow::CowNamedVec;
+
+pub mod csv;
+
+mod deque;
+pub use deque::NamedVecDeque;
+
+mod invariants;
+pub use invariants::InvariantViolation;
+
+#[cfg(feature = "std")]
+pub mod expiry;
+#[cfg(feature = "std")]
+pub use expiry::Expiring;
+
+mod group;
+pub use group::Group;
+
+pub mod join;
+
+pub mod journal;
+pub use journal::Journaled;
+
+mod mapping;
+pub use mapping::MapError;
+
+pub mod lru;
+pub use lru::NamedLru;
+
+pub mod observe;
+pub use observe::Observed;
+
+mod sorted;
+pub use sorted::SortedNamedVec;
+
+mod table;
+pub use table::DisplayTable;
+
+pub mod testing;
+
+mod transaction;
+pub use transaction::Transaction;
+
+#[cfg(feature = "std")]
+mod concurrent;
+#[cfg(feature = "std")]
+pub use concurrent::{ConcurrentNamedVec, RenameError};
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+#[cfg(feature = "persistent")]
+mod persistent;
+#[cfg(feature = "persistent")]
+pub use persistent::PersistentNamedVec;
+
+/////////
+// Map //
+/////////
+
+// Index from names to positions. Without `std` there is no default hasher,
+// so `no_std` builds use a `BTreeMap` instead.
+#[cfg(feature = "std")]
+type Map = std::collections::HashMap<String, usize>;
+#[cfg(not(feature = "std"))]
+type Map = alloc::collections::BTreeMap<String, usize>;
+
+#[cfg(feature = "std")]
+fn map_with_capacity(capacity: usize) -> Map {
+    Map::with_capacity(capacity)
+}
+
+#[cfg(not(feature = "std"))]
+fn map_with_capacity(_capacity: usize) -> Map {
+    Map::new()
+}
+
+// Below this many elements, looking a name up by scanning `items` is faster than hashing it
+const DEFAULT_INDEX_THRESHOLD: usize = 16;
+
+// FNV-1a, for indexes that can't use a randomly seeded hasher
+// (because `core` has none, or because the hash is stored)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
 
 ///////////
 // Named //
@@ -9,6 +137,12 @@ pub trait Named {
     fn name(&self) -> &str;
 }
 
+impl<T: Named + ?Sized> Named for &T {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+}
+
 ////////////
 // Lookup //
 ////////////
@@ -19,9 +153,11 @@ pub trait Named {
 /// are designed to avoid making the user have to create a `Lookup`.
 /// Prefer `named_vec.get("foo")` to `named_vec.get(Lookup::Name("foo"))`
 /// and `named_vec.get(0)` to `named_vec.get(Lookup::Index(0))`
+#[derive(Debug, Clone, Copy)]
 pub enum Lookup<'a> {
     Name(&'a str),
     Index(usize),
+    Handle(Handle),
 }
 
 impl<'a> From<&'a str> for Lookup<'a> {
@@ -30,12 +166,77 @@ impl<'a> From<&'a str> for Lookup<'a> {
     }
 }
 
-impl<'a> From<usize> for Lookup<'a> {
+impl From<usize> for Lookup<'_> {
     fn from(i: usize) -> Self {
         Lookup::Index(i)
     }
 }
 
+impl From<Handle> for Lookup<'_> {
+    fn from(h: Handle) -> Self {
+        Lookup::Handle(h)
+    }
+}
+
+/// Error returned when a `Lookup` does not refer to an element.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LookupError {
+    /// The index is out of bounds.
+    Index(usize),
+    /// No element has this name.
+    Name(String),
+    /// The handle's element has been removed.
+    Handle(Handle),
+}
+
+impl<'a> From<Lookup<'a>> for LookupError {
+    fn from(lookup: Lookup<'a>) -> Self {
+        match lookup {
+            Lookup::Name(name) => LookupError::Name(name.to_owned()),
+            Lookup::Index(index) => LookupError::Index(index),
+            Lookup::Handle(handle) => LookupError::Handle(handle),
+        }
+    }
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LookupError::Index(index) => write!(f, "index {} is out of bounds", index),
+            LookupError::Name(ref name) => write!(f, "no element is named {:?}", name),
+            LookupError::Handle(_) => write!(f, "handle refers to a removed element"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LookupError {}
+
+////////////
+// Handle //
+////////////
+
+/// A stable reference to an element in a `NamedVec`.
+///
+/// Unlike an index or a name, a `Handle` keeps referring to the same element
+/// when it is moved by [`insert()`](struct.NamedVec.html#method.insert),
+/// [`remove()`](struct.NamedVec.html#method.remove) or
+/// [`swap()`](struct.NamedVec.html#method.swap), and resolving it is O(1).
+///
+/// Once its element has been removed, a `Handle` is stale and lookups with it return `None`,
+/// even if the element's slot has since been reused by a new element.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Handle {
+    slot: usize,
+    generation: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    generation: u64,
+    index: Option<usize>,
+}
+
 /// Vector where each element has an associated name.
 ///
 /// Elements must implement the [`Named`](trait.Named.html) trait so that they can be accessed
@@ -44,51 +245,123 @@ impl<'a> From<usize> for Lookup<'a> {
 /// an existing element.
 ///
 /// Internally, a `NamedVec<T>` is a `Vec<T>` with names
-/// and their corresponding indices stored as a `HashMap<String, usize>`.
+/// and their corresponding indices stored as a `HashMap<String, usize>`
+/// (or a `BTreeMap<String, usize>` when the `std` feature is disabled).
+/// Small collections skip the map and find names by scanning the `Vec<T>`;
+/// see [`with_index_threshold()`](#method.with_index_threshold).
 
 //////////////
 // NamedVec //
 //////////////
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Clone)]
 pub struct NamedVec<T: Named> {
-    map: HashMap<String, usize>,
+    // Only built once `items` grows past `index_threshold`
+    map: Option<Map>,
+    index_threshold: usize,
     items: Vec<T>,
+    // Slot of each element in `items`, kept in the same order
+    item_slots: Vec<usize>,
+    slots: Vec<Slot>,
+    free_slots: Vec<usize>,
 }
 
 impl<T: Named> NamedVec<T> {
     /// Creates an empty `NamedVec<T>`.
     pub fn new() -> Self {
         NamedVec {
-            map: HashMap::new(),
+            map: None,
+            index_threshold: DEFAULT_INDEX_THRESHOLD,
             items: Vec::new(),
+            item_slots: Vec::new(),
+            slots: Vec::new(),
+            free_slots: Vec::new(),
         }
     }
 
     /// Creates an empty `NamedVec<T>` with both an underlying `Vec<T>` and
     /// `HashMap` of the specified capacity.
     ///
+    /// Without the `std` feature, only the `Vec<T>` is preallocated.
+    ///
     /// The vector will be able to hold exactly `capacity` elements without
     /// relocating. If `capacity` is 0, the vector will not allocate.
     pub fn with_capacity(capacity: usize) -> Self {
+        let map = if capacity > DEFAULT_INDEX_THRESHOLD {
+            Some(map_with_capacity(capacity))
+        } else {
+            None
+        };
+
         NamedVec {
-            map: HashMap::with_capacity(capacity),
+            map,
+            index_threshold: DEFAULT_INDEX_THRESHOLD,
             items: Vec::with_capacity(capacity),
+            item_slots: Vec::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            free_slots: Vec::new(),
         }
     }
 
+    /// Creates an empty `NamedVec<T>` that finds names by scanning its elements until
+    /// it holds more than `threshold` of them, and only then builds its `HashMap`.
+    ///
+    /// Scanning avoids hashing names and allocating a `String` per element, which
+    /// is faster for small collections. `NamedVec::new()` uses a threshold of 16.
+    /// A threshold of 0 always uses the `HashMap`.
+    pub fn with_index_threshold(threshold: usize) -> Self {
+        let mut named_vec = NamedVec::new();
+        named_vec.index_threshold = threshold;
+        named_vec
+    }
+
+    /// Returns the number of elements above which names are looked up in a `HashMap`.
+    ///
+    /// See [`with_index_threshold()`](#method.with_index_threshold) for more information.
+    pub fn index_threshold(&self) -> usize {
+        self.index_threshold
+    }
+
+    /// Changes the number of elements above which names are looked up in a `HashMap`,
+    /// building it immediately if the vector is already larger than `threshold`.
+    ///
+    /// Once built, the `HashMap` is kept even if the vector shrinks or `threshold` is raised.
+    pub fn set_index_threshold(&mut self, threshold: usize) {
+        self.index_threshold = threshold;
+        self.build_index_if_needed();
+        self.debug_check_invariants();
+    }
+
     /// Appends an element to the back of the collection,
     /// or replaces an element with the same name if one exists.
     pub fn push(&mut self, value: T) {
-        match self.map.get(value.name()).map(|n| n.clone()) {
+        self.push_with_handle(value);
+    }
+
+    /// Like [`push()`](#method.push), but returns a [`Handle`](struct.Handle.html)
+    /// to the element.
+    ///
+    /// If an element with the same name is replaced, its existing handle is returned.
+    pub fn push_with_handle(&mut self, value: T) -> Handle {
+        let handle = match self.position(value.name()) {
             Some(i) => {
                 self.items[i] = value;
+                self.handle_at(i)
             },
             None => {
-                self.map.insert(value.name().to_owned(), self.items.len());
+                let index = self.items.len();
+                if let Some(ref mut map) = self.map {
+                    map.insert(value.name().to_owned(), index);
+                }
                 self.items.push(value);
+                let slot = self.allocate_slot(index);
+                self.item_slots.push(slot);
+                self.build_index_if_needed();
+                self.handle_at(index)
             },
-        }
+        };
+        self.debug_check_invariants();
+        handle
     }
 
     /// Inserts an element at position `index` (shifting all elements after it to the right),
@@ -101,26 +374,47 @@ impl<T: Named> NamedVec<T> {
     ///
     /// Panics if `index` is out of bounds.
     pub fn insert(&mut self, index: usize, value: T) {
-        match self.map.get(value.name()).map(|n| n.clone()) {
+        self.insert_with_handle(index, value);
+    }
+
+    /// Like [`insert()`](#method.insert), but returns a [`Handle`](struct.Handle.html)
+    /// to the element.
+    ///
+    /// If an element with the same name is replaced, its existing handle is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn insert_with_handle(&mut self, index: usize, value: T) -> Handle {
+        let handle = match self.position(value.name()) {
             Some(i) => {
                 self.items[i] = value;
+                self.handle_at(i)
             },
             None => {
-                let name = value.name().to_owned();
                 self.items.insert(index, value);
-                for i in self.map.values_mut() {
-                    if i >= &mut (index.clone()) {
-                        *i += 1;
+                if let Some(ref mut map) = self.map {
+                    for i in map.values_mut() {
+                        if *i >= index {
+                            *i += 1;
+                        }
                     }
+                    map.insert(self.items[index].name().to_owned(), index);
                 }
-                self.map.insert(name, index);
+                let slot = self.allocate_slot(index);
+                self.item_slots.insert(index, slot);
+                self.reindex_slots(index + 1);
+                self.build_index_if_needed();
+                self.handle_at(index)
             },
-        }
+        };
+        self.debug_check_invariants();
+        handle
     }
 
     /// Removes and returns the element specified by `lookup`
     /// within the vector, shifting all elements after it to the left.
-    /// `lookup` can be either a `usize` index or a `&str` name.
+    /// `lookup` can be either a `usize` index, a `&str` name or a `Handle`.
     ///
     /// Note that removing an item with this method requires
     /// iterating over the internal `HashMap`, which is a linear operation.
@@ -129,35 +423,49 @@ impl<T: Named> NamedVec<T> {
     ///
     /// * Panics if a `usize` argument is out of bounds.
     /// * Panics if a `&str` argument is an invalid name.
-    pub fn remove<'a, A: 'a>(&mut self, lookup: A) -> T
-    where A: Into<Lookup<'a>> + Copy {
+    /// * Panics if a `Handle` argument is stale.
+    pub fn remove<'a, A>(&mut self, lookup: A) -> T
+    where A: 'a + Into<Lookup<'a>> + Copy {
         let index = match lookup.into() {
             Lookup::Name(name) => {
-                let index = self.map[name].clone();
-                self.map.remove(name);
-                index
+                self.position(name).expect("invalid name")
             },
             Lookup::Index(index) => {
-                let name = self.items[index].name();
-                self.map.remove(name);
                 index
             },
+            Lookup::Handle(handle) => {
+                self.index_from_handle(handle).expect("stale handle")
+            },
         };
 
-        for i in self.map.values_mut() {
-            if i >= &mut (index.clone()) {
-                *i -= 1;
+        if let Some(ref mut map) = self.map {
+            map.remove(self.items[index].name());
+            for i in map.values_mut() {
+                if *i > index {
+                    *i -= 1;
+                }
             }
         }
-        self.items.remove(index)
+        let slot = self.item_slots.remove(index);
+        self.free_slot(slot);
+        self.reindex_slots(index);
+        let item = self.items.remove(index);
+        self.debug_check_invariants();
+        item
     }
 
     /// Returns the number of elements the vector can hold without reallocating.
     pub fn capacity(&self) -> usize {
-        std::cmp::min(
-            self.items.capacity(),
-            self.map.capacity(),
-        )
+        #[cfg(feature = "std")]
+        {
+            if let Some(ref map) = self.map {
+                return core::cmp::min(
+                    self.items.capacity(),
+                    map.capacity(),
+                );
+            }
+        }
+        self.items.capacity()
     }
 
     /// Reserves capacity for at least `additional` more elements to be inserted in
@@ -169,13 +477,25 @@ impl<T: Named> NamedVec<T> {
     /// Panics if the new allocation size overflows `usize`.
     pub fn reserve(&mut self, additional: usize) {
         self.items.reserve(additional);
-        self.map.reserve(additional);
+        self.item_slots.reserve(additional);
+        #[cfg(feature = "std")]
+        {
+            if let Some(ref mut map) = self.map {
+                map.reserve(additional);
+            }
+        }
     }
 
     /// Shrinks the capacity as much as possible.
     pub fn shrink_to_fit(&mut self) {
         self.items.shrink_to_fit();
-        self.map.shrink_to_fit();
+        self.item_slots.shrink_to_fit();
+        #[cfg(feature = "std")]
+        {
+            if let Some(ref mut map) = self.map {
+                map.shrink_to_fit();
+            }
+        }
     }
 
     /// Shortens the vector, keeping the first `len` elements and dropping the rest.
@@ -183,19 +503,22 @@ impl<T: Named> NamedVec<T> {
     /// If `len` is greater than the vector's current length, this has no effect.
     pub fn truncate(&mut self, len: usize) {
         if len < self.len() {
-            let max = self.map.values().max().map(|n| n.clone()).unwrap();
-            for item in self.items[len..max+1].iter() {
-                let name = item.name();
-                self.map.remove(name);
+            if let Some(ref mut map) = self.map {
+                for item in self.items[len..].iter() {
+                    map.remove(item.name());
+                }
+            }
+            for slot in self.item_slots.split_off(len) {
+                self.free_slot(slot);
             }
             self.items.truncate(len);
         }
+        self.debug_check_invariants();
     }
 
     /// Clears the vector, removing all values.
     pub fn clear(&mut self) {
-        self.map.clear();
-        self.items.clear();
+        self.truncate(0);
     }
 
     /// Returns `true` if the vector contains no elements.
@@ -206,23 +529,33 @@ impl<T: Named> NamedVec<T> {
     /// Returns a reference to an element.
     ///
     /// This function's argument can be a `usize`, e.g. `named_vec.get(0)`,
-    /// or a `&str`, e.g. `named_vec.get("foo")`.
-    /// These will access elements by position or name, respectively.
+    /// a `&str`, e.g. `named_vec.get("foo")`, or a [`Handle`](struct.Handle.html).
+    /// These will access elements by position, name or handle, respectively.
     ///
-    /// Returns `None` if a `usize` argument is out of bounds or if
-    /// a `&str` argument refers to a nonexistent element.
-    pub fn get<'a, A: 'a>(&self, lookup: A) -> Option<&T> where A: Into<Lookup<'a>> {
+    /// Returns `None` if a `usize` argument is out of bounds, if
+    /// a `&str` argument refers to a nonexistent element or if a `Handle` is stale.
+    pub fn get<'a, A>(&self, lookup: A) -> Option<&T> where A: 'a + Into<Lookup<'a>> {
         self.index_from_lookup(lookup.into()).and_then(|i| self.items.get(i))
     }
 
     /// Returns a mutable reference to an element.
     ///
     /// See [`get()`](#method.get) for more information.
-    pub fn get_mut<'a, A: 'a>(&mut self, lookup: A) -> Option<&mut T>
-    where A: Into<Lookup<'a>> {
+    pub fn get_mut<'a, A>(&mut self, lookup: A) -> Option<&mut T>
+    where A: 'a + Into<Lookup<'a>> {
         self.index_from_lookup(lookup.into()).and_then(move |i| self.items.get_mut(i))
     }
 
+    /// Returns a [`Handle`](struct.Handle.html) to an element.
+    ///
+    /// See [`get()`](#method.get) for more information on arguments.
+    pub fn handle<'a, A>(&self, lookup: A) -> Option<Handle> where A: 'a + Into<Lookup<'a>> {
+        self.index_from_lookup(lookup.into())
+            .filter(|&i| i < self.len())
+            .map(|i| self.handle_at(i))
+    }
+
+
     /// Returns a reference to a subslice.
     ///
     /// Unlike [`get()`](#method.get), this function only accepts range types.
@@ -270,29 +603,33 @@ impl<T: Named> NamedVec<T> {
 
     /// Swaps two elements.
     ///
-    /// Each element can be either a `usize` or a `&str`.
+    /// Each element can be either a `usize`, a `&str` or a `Handle`.
     /// See [`get()`](#method.get) for more information on arguments.
     ///
     /// # Panics
     ///
     /// * Panics if a `usize` argument is out of bounds.
     /// * Panics if a `&str` argument is an invalid name.
-    pub fn swap<'a, 'b, A: 'a, B: 'b>(&mut self, first: A, second: B)
-    where A: Into<Lookup<'a>> + Copy, B: Into<Lookup<'b>> + Copy {
+    /// * Panics if a `Handle` argument is stale.
+    pub fn swap<'a, 'b, A, B>(&mut self, first: A, second: B)
+    where A: 'a + Into<Lookup<'a>> + Copy, B: 'b + Into<Lookup<'b>> + Copy {
         let old_i1 = self.index_from_lookup(first.into()).unwrap();
         let old_i2 = self.index_from_lookup(second.into()).unwrap();
 
-        // Don't bother swapping (and allocating Strings!) if the two items are the same
+        // Don't bother swapping if the two items are the same
         if old_i1 == old_i2 {
             return;
         }
 
-        let old_s1 = self.name_from_lookup(first.into()).unwrap();
-        let old_s2 = self.name_from_lookup(second.into()).unwrap();
-
-        self.map.insert(old_s1, old_i2);
-        self.map.insert(old_s2, old_i1);
+        if let Some(ref mut map) = self.map {
+            *map.get_mut(self.items[old_i1].name()).unwrap() = old_i2;
+            *map.get_mut(self.items[old_i2].name()).unwrap() = old_i1;
+        }
         self.items.swap(old_i1, old_i2);
+        self.item_slots.swap(old_i1, old_i2);
+        self.slots[self.item_slots[old_i1]].index = Some(old_i1);
+        self.slots[self.item_slots[old_i2]].index = Some(old_i2);
+        self.debug_check_invariants();
     }
 
     /// Returns the number of elements in the vector.
@@ -302,55 +639,171 @@ impl<T: Named> NamedVec<T> {
 
     /// Removes the last element from the vector and returns in, or `None` if it is empty.
     pub fn pop(&mut self) -> Option<T> {
-        if self.items.len() == 0 {
+        if self.items.is_empty() {
             None
         } else {
             let last_item = self.items.pop().unwrap();
-            self.map.remove(last_item.name());
+            if let Some(ref mut map) = self.map {
+                map.remove(last_item.name());
+            }
+            let slot = self.item_slots.pop().unwrap();
+            self.free_slot(slot);
+            self.debug_check_invariants();
             Some(last_item)
         }
     }
 
+    /// Returns `true` if both vectors have the same elements under the same names,
+    /// regardless of their order.
+    ///
+    /// `==` also requires the elements to be in the same order.
+    pub fn eq_ignoring_order(&self, other: &Self) -> bool where T: PartialEq {
+        self.len() == other.len()
+            && self.items.iter().all(|item| other.get(item.name()) == Some(item))
+    }
+
     fn index_from_lookup(&self, lookup: Lookup) -> Option<usize> {
         match lookup {
             Lookup::Name(name) => {
-                self.map.get(name).cloned()
+                self.position(name)
             },
             Lookup::Index(index) => {
                 Some(index)
             },
+            Lookup::Handle(handle) => {
+                self.index_from_handle(handle)
+            },
         }
     }
 
-    fn name_from_lookup(&self, lookup: Lookup) -> Option<String> {
-        match lookup {
-            Lookup::Name(name) => {
-                Some(name.to_owned())
+    fn position(&self, name: &str) -> Option<usize> {
+        match self.map {
+            Some(ref map) => map.get(name).cloned(),
+            None => self.items.iter().position(|item| item.name() == name),
+        }
+    }
+
+    fn build_index_if_needed(&mut self) {
+        if self.map.is_none() && self.items.len() > self.index_threshold {
+            self.build_index();
+        }
+    }
+
+    fn build_index(&mut self) {
+        let mut map = map_with_capacity(self.items.capacity());
+        for (i, item) in self.items.iter().enumerate() {
+            if !map.contains_key(item.name()) {
+                map.insert(item.name().to_owned(), i);
+            }
+        }
+        self.map = Some(map);
+    }
+
+    fn index_from_handle(&self, handle: Handle) -> Option<usize> {
+        self.slots.get(handle.slot)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.index)
+    }
+
+    fn handle_at(&self, index: usize) -> Handle {
+        let slot = self.item_slots[index];
+        Handle {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    fn allocate_slot(&mut self, index: usize) -> usize {
+        match self.free_slots.pop() {
+            Some(slot) => {
+                self.slots[slot].index = Some(index);
+                slot
             },
-            Lookup::Index(index) => {
-                self.items.get(index).and_then(|s| Some(String::from(s.name())))
+            None => {
+                self.slots.push(Slot { generation: 0, index: Some(index) });
+                self.slots.len() - 1
             },
         }
     }
 
+    fn free_slot(&mut self, slot: usize) {
+        self.slots[slot].index = None;
+        self.slots[slot].generation += 1;
+        self.free_slots.push(slot);
+    }
+
+    #[inline]
+    fn debug_check_invariants(&self) {
+        #[cfg(feature = "debug-invariants")]
+        {
+            if let Err(violation) = self.check_invariants() {
+                panic!("NamedVec invariant violated: {}", violation);
+            }
+        }
+    }
+
+    // Points the slots of every element from `start` onwards back at that element
+    fn reindex_slots(&mut self, start: usize) {
+        for (i, &slot) in self.item_slots.iter().enumerate().skip(start) {
+            self.slots[slot].index = Some(i);
+        }
+    }
+
+    // Keeps the first element with each name, as documented on `named_vec!`
     #[doc(hidden)]
     pub fn from_box(items: Box<[T]>) -> Self {
         let vec = <[_]>::into_vec(items);
 
-        let mut counter: usize = 0;
-        let mut map = HashMap::new();
-
-        for item in &vec {
-            if let None = map.get(item.name()) {
-                map.insert(item.name().to_owned(), counter);
-                counter += 1;
+        let mut named_vec = NamedVec::with_capacity(vec.len());
+        for item in vec {
+            if named_vec.position(item.name()).is_none() {
+                named_vec.push(item);
             }
         }
+        named_vec
+    }
+}
 
-        NamedVec {
-            map: map,
-            items: vec,
-        }
+impl<T: Named> Default for NamedVec<T> {
+    fn default() -> Self {
+        NamedVec::new()
+    }
+}
+
+/// Formats the elements as a map from names to elements, in index order.
+impl<T: Named + fmt::Debug> fmt::Debug for NamedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.items.iter().map(|item| (item.name(), item)))
+            .finish()
+    }
+}
+
+impl<T: Named + PartialEq> PartialEq for NamedVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<T: Named + Eq> Eq for NamedVec<T> {}
+
+impl<T: Named + Hash> Hash for NamedVec<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash(state);
+    }
+}
+
+/// Compares elements lexicographically, in index order.
+impl<T: Named + PartialOrd> PartialOrd for NamedVec<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.items.partial_cmp(&other.items)
+    }
+}
+
+/// Compares elements lexicographically, in index order.
+impl<T: Named + Ord> Ord for NamedVec<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.items.cmp(&other.items)
     }
 }
 
@@ -369,27 +822,48 @@ impl<T: Named> IntoIterator for NamedVec<T> {
     }
 }
 
-impl <'a, T: Named> IntoIterator for &'a NamedVec<T> {
+impl<'a, T: Named> IntoIterator for &'a NamedVec<T> {
     type Item = &'a T;
-    type IntoIter = std::slice::Iter<'a, T>;
+    type IntoIter = core::slice::Iter<'a, T>;
 
-    fn into_iter(self) -> std::slice::Iter<'a, T> {
+    fn into_iter(self) -> core::slice::Iter<'a, T> {
         self.items.iter()
     }
 }
 
-impl <'a, T: Named> IntoIterator for &'a mut NamedVec<T> {
+impl<'a, T: Named> IntoIterator for &'a mut NamedVec<T> {
     type Item = &'a mut T;
-    type IntoIter = std::slice::IterMut<'a, T>;
+    type IntoIter = core::slice::IterMut<'a, T>;
 
-    fn into_iter(self) -> std::slice::IterMut<'a, T> {
+    fn into_iter(self) -> core::slice::IterMut<'a, T> {
         self.items.iter_mut()
     }
 }
 
+impl<T: Named> FromIterator<T> for NamedVec<T> {
+    /// Collects elements in order, with each element replacing any earlier one
+    /// that has the same name.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut named_vec = NamedVec::new();
+        named_vec.extend(iter);
+        named_vec
+    }
+}
+
+impl<T: Named> Extend<T> for NamedVec<T> {
+    /// Pushes each element in order.
+    ///
+    /// See [`push()`](#method.push) for more information.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
 /// Iterator over the items in a `NamedVec<T>`
 pub struct IntoIter<T: Named> {
-    items: std::vec::IntoIter<T>,
+    items: alloc::vec::IntoIter<T>,
 }
 
 impl<T: Named> Iterator for IntoIter<T> {
@@ -404,7 +878,7 @@ impl<T: Named> Iterator for IntoIter<T> {
 // Index //
 ///////////
 
-impl<'a, T: Named> Index<&'a str> for NamedVec<T> {
+impl<T: Named> Index<&str> for NamedVec<T> {
     type Output = T;
 
     fn index(&self, index: &str) -> &T {
@@ -479,25 +953,25 @@ pub enum MultiLookup {
 //     }
 // }
 
-impl<'a> From<Range<usize>> for MultiLookup {
+impl From<Range<usize>> for MultiLookup {
     fn from(i: Range<usize>) -> Self {
         MultiLookup::Range(i)
     }
 }
 
-impl<'a> From<RangeFrom<usize>> for MultiLookup {
+impl From<RangeFrom<usize>> for MultiLookup {
     fn from(i: RangeFrom<usize>) -> Self {
         MultiLookup::RangeFrom(i)
     }
 }
 
-impl<'a> From<RangeTo<usize>> for MultiLookup {
+impl From<RangeTo<usize>> for MultiLookup {
     fn from(i: RangeTo<usize>) -> Self {
         MultiLookup::RangeTo(i)
     }
 }
 
-impl<'a> From<RangeFull> for MultiLookup {
+impl From<RangeFull> for MultiLookup {
     fn from(i: RangeFull) -> Self {
         MultiLookup::RangeFull(i)
     }
@@ -517,7 +991,12 @@ impl<'a> From<RangeFull> for MultiLookup {
 #[macro_export]
 macro_rules! named_vec {
     ($($x:expr),*) => (
-        NamedVec::from_box(Box::new([$($x),*]))
+        $crate::NamedVec::from_box($crate::__private::Box::new([$($x),*]))
     );
     ($($x:expr,)*) => (named_vec![$($x),*])
 }
+
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::boxed::Box;
+}
@@ -0,0 +1,250 @@
+//! Size-limited cache of named elements with least-recently-used eviction.
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use {map_with_capacity, Map, Named};
+
+// Marks the end of the recency list
+const NIL: usize = usize::MAX;
+
+type Evictor<T> = Box<dyn FnMut(&T)>;
+
+struct Node<T> {
+    item: Option<T>,
+    // Neighbours towards the most and least recently used ends
+    prev: usize,
+    next: usize,
+}
+
+/// Collection of at most `capacity` named elements, which evicts the least recently used
+/// element to make room for a new one.
+///
+/// Elements are kept in a doubly linked list ordered by recency, and names are looked up in
+/// a `HashMap` (or a `BTreeMap` without the `std` feature), so lookups and promotions
+/// don't have to move other elements.
+///
+/// [`get()`](#method.get), [`get_mut()`](#method.get_mut) and [`push()`](#method.push) make an
+/// element the most recently used; [`peek()`](#method.peek) does not.
+pub struct NamedLru<T: Named> {
+    nodes: Vec<Node<T>>,
+    free_nodes: Vec<usize>,
+    map: Map,
+    // Most and least recently used nodes
+    head: usize,
+    tail: usize,
+    capacity: usize,
+    on_evict: Option<Evictor<T>>,
+}
+
+impl<T: Named> NamedLru<T> {
+    /// Creates an empty `NamedLru<T>` that holds at most `capacity` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "NamedLru capacity must be nonzero");
+        NamedLru {
+            nodes: Vec::new(),
+            free_nodes: Vec::new(),
+            map: map_with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    /// Returns the maximum number of elements.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if an element is named `name`, without promoting it.
+    pub fn contains(&self, name: &str) -> bool {
+        self.map.contains_key(name)
+    }
+
+    /// Sets a function to be called with each element that is evicted to make room
+    /// for a new one, replacing any previous function.
+    ///
+    /// It is not called for elements removed by [`remove()`](#method.remove),
+    /// [`pop_lru()`](#method.pop_lru) or [`clear()`](#method.clear).
+    pub fn on_evict<F>(&mut self, f: F) where F: FnMut(&T) + 'static {
+        self.on_evict = Some(Box::new(f));
+    }
+
+    /// Adds an element as the most recently used, or replaces and promotes an element with
+    /// the same name if one exists.
+    ///
+    /// If a new element exceeds the capacity, the least recently used element is evicted
+    /// and returned.
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if let Some(&node) = self.map.get(value.name()) {
+            self.nodes[node].item = Some(value);
+            self.promote(node);
+            return None;
+        }
+
+        let evicted = if self.len() == self.capacity { self.pop_lru() } else { None };
+        if let (Some(item), Some(on_evict)) = (evicted.as_ref(), self.on_evict.as_mut()) {
+            on_evict(item);
+        }
+
+        let name = value.name().to_owned();
+        let node = match self.free_nodes.pop() {
+            Some(node) => {
+                self.nodes[node].item = Some(value);
+                node
+            },
+            None => {
+                self.nodes.push(Node { item: Some(value), prev: NIL, next: NIL });
+                self.nodes.len() - 1
+            },
+        };
+        self.map.insert(name, node);
+        self.link_front(node);
+        evicted
+    }
+
+    /// Returns a reference to the element named `name` and makes it the most recently used.
+    pub fn get(&mut self, name: &str) -> Option<&T> {
+        let node = *self.map.get(name)?;
+        self.promote(node);
+        self.nodes[node].item.as_ref()
+    }
+
+    /// Returns a mutable reference to the element named `name` and makes it the most
+    /// recently used.
+    ///
+    /// The element's name must not be changed.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut T> {
+        let node = *self.map.get(name)?;
+        self.promote(node);
+        self.nodes[node].item.as_mut()
+    }
+
+    /// Returns a reference to the element named `name` without changing its recency.
+    pub fn peek(&self, name: &str) -> Option<&T> {
+        let node = *self.map.get(name)?;
+        self.nodes[node].item.as_ref()
+    }
+
+    /// Returns the least recently used element, which is the next to be evicted.
+    pub fn peek_lru(&self) -> Option<&T> {
+        self.nodes.get(self.tail).and_then(|node| node.item.as_ref())
+    }
+
+    /// Removes and returns the element named `name`, or `None` if there is no such element.
+    pub fn remove(&mut self, name: &str) -> Option<T> {
+        let node = self.map.remove(name)?;
+        self.unlink(node);
+        self.free_nodes.push(node);
+        self.nodes[node].item.take()
+    }
+
+    /// Removes and returns the least recently used element, or `None` if there are none.
+    pub fn pop_lru(&mut self) -> Option<T> {
+        if self.tail == NIL {
+            return None;
+        }
+        let node = self.tail;
+        self.unlink(node);
+        self.free_nodes.push(node);
+        let item = self.nodes[node].item.take().unwrap();
+        self.map.remove(item.name());
+        Some(item)
+    }
+
+    /// Removes every element.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free_nodes.clear();
+        self.map.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    /// Returns an iterator over the elements from most to least recently used.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            lru: self,
+            node: self.head,
+        }
+    }
+
+    fn promote(&mut self, node: usize) {
+        if self.head != node {
+            self.unlink(node);
+            self.link_front(node);
+        }
+    }
+
+    fn link_front(&mut self, node: usize) {
+        self.nodes[node].prev = NIL;
+        self.nodes[node].next = self.head;
+        match self.head {
+            NIL => self.tail = node,
+            head => self.nodes[head].prev = node,
+        }
+        self.head = node;
+    }
+
+    fn unlink(&mut self, node: usize) {
+        let Node { prev, next, .. } = self.nodes[node];
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+}
+
+impl<T: Named + fmt::Debug> fmt::Debug for NamedLru<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|item| (item.name(), item)))
+            .finish()
+    }
+}
+
+impl<'a, T: Named> IntoIterator for &'a NamedLru<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of a `NamedLru`, from most to least recently used
+pub struct Iter<'a, T: Named + 'a> {
+    lru: &'a NamedLru<T>,
+    node: usize,
+}
+
+impl<'a, T: Named> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.lru.nodes.get(self.node)?;
+        self.node = node.next;
+        node.item.as_ref()
+    }
+}
@@ -0,0 +1,105 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt;
+
+use {Named, NamedVec};
+
+/// Error returned by [`NamedVec::try_map()`](struct.NamedVec.html#method.try_map).
+#[derive(Debug, PartialEq, Clone)]
+pub enum MapError<E> {
+    /// The function failed on the element named `name`.
+    Failed { name: String, error: E },
+    /// The function returned an element named `found` for the element at `index`,
+    /// which is named `expected`.
+    NameMismatch { index: usize, expected: String, found: String },
+}
+
+impl<E: fmt::Display> fmt::Display for MapError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MapError::Failed { ref name, ref error } => {
+                write!(f, "{:?}: {}", name, error)
+            },
+            MapError::NameMismatch { index, ref expected, ref found } => {
+                write!(f, "element {} was renamed from {:?} to {:?}", index, expected, found)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + fmt::Display> std::error::Error for MapError<E> {}
+
+impl<T: Named> NamedVec<T> {
+    /// Converts each element with `f`, keeping the order and reusing the name index.
+    ///
+    /// `f` must return an element with the same name as the one it was given; handles to
+    /// elements of `self` keep working with the result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns an element with a different name.
+    pub fn map<U, F>(self, mut f: F) -> NamedVec<U> where U: Named, F: FnMut(T) -> U {
+        match self.map_items(|item| Ok::<U, Infallible>(f(item))) {
+            Ok(named_vec) => named_vec,
+            Err(MapError::NameMismatch { index, expected, found }) => {
+                panic!("map renamed element {} from {:?} to {:?}", index, expected, found)
+            },
+            Err(MapError::Failed { error, .. }) => match error {},
+        }
+    }
+
+    /// Like [`map()`](#method.map), but `f` can fail.
+    ///
+    /// Stops at the first element for which `f` returns an error or an element with a
+    /// different name, and reports that element's name.
+    pub fn try_map<U, E, F>(self, f: F) -> Result<NamedVec<U>, MapError<E>>
+    where U: Named, F: FnMut(T) -> Result<U, E> {
+        self.map_items(f)
+    }
+
+    fn map_items<U, E, F>(self, mut f: F) -> Result<NamedVec<U>, MapError<E>>
+    where U: Named, F: FnMut(T) -> Result<U, E> {
+        let NamedVec { map, index_threshold, items, item_slots, slots, free_slots } = self;
+
+        // Without the name index, the names have to be saved to check them afterwards
+        let names: Option<Vec<String>> = match map {
+            Some(_) => None,
+            None => Some(items.iter().map(|item| item.name().to_owned()).collect()),
+        };
+        let name_at = |index: usize| -> String {
+            match names {
+                Some(ref names) => names[index].clone(),
+                None => map.iter().flatten().find(|&(_, &i)| i == index).unwrap().0.clone(),
+            }
+        };
+
+        let mut mapped = Vec::with_capacity(items.len());
+        for (index, item) in items.into_iter().enumerate() {
+            let new = f(item).map_err(|error| MapError::Failed { name: name_at(index), error })?;
+            let same_name = match names {
+                Some(ref names) => names[index] == new.name(),
+                None => map.as_ref().and_then(|map| map.get(new.name())) == Some(&index),
+            };
+            if !same_name {
+                return Err(MapError::NameMismatch {
+                    index,
+                    expected: name_at(index),
+                    found: new.name().to_owned(),
+                });
+            }
+            mapped.push(new);
+        }
+
+        Ok(NamedVec {
+            map,
+            index_threshold,
+            items: mapped,
+            item_slots,
+            slots,
+            free_slots,
+        })
+    }
+}
@@ -0,0 +1,196 @@
+//! Change notifications for `NamedVec`.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+use core::ops::Deref;
+
+use {Lookup, Named, NamedVec};
+
+/// A change made to an [`Observed`](struct.Observed.html) vector.
+///
+/// Observers are notified after the change has been applied.
+#[derive(Debug, PartialEq)]
+pub enum Change<'a, T: 'a> {
+    /// A new element named `name` was inserted at `index`.
+    Inserted { index: usize, name: &'a str },
+    /// `item` was removed from `index`.
+    Removed { index: usize, item: &'a T },
+    /// The element at `index` was replaced by one with the same name.
+    Replaced { index: usize, old: &'a T },
+    /// The element at `from` was moved to `to`.
+    Moved { from: usize, to: usize },
+    /// Every element was removed.
+    Cleared,
+}
+
+/// Identifies an observer registered with [`Observed::subscribe()`](struct.Observed.html#method.subscribe).
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Subscription(usize);
+
+type Observer<T> = Box<dyn FnMut(&Change<T>)>;
+
+/// `NamedVec` that notifies observers of every change.
+///
+/// `Observed<T>` dereferences to `NamedVec<T>` for reading; all mutation has to go through
+/// its own methods so that observers can be notified.
+pub struct Observed<T: Named> {
+    named_vec: NamedVec<T>,
+    observers: Vec<(Subscription, Observer<T>)>,
+    next_subscription: usize,
+}
+
+impl<T: Named> Observed<T> {
+    /// Creates an empty `Observed<T>`.
+    pub fn new() -> Self {
+        Observed::from(NamedVec::new())
+    }
+
+    /// Returns the underlying `NamedVec<T>`, dropping every observer.
+    pub fn into_inner(self) -> NamedVec<T> {
+        self.named_vec
+    }
+
+    /// Registers `observer` to be called after every change.
+    pub fn subscribe<F>(&mut self, observer: F) -> Subscription
+    where F: FnMut(&Change<T>) + 'static {
+        let subscription = Subscription(self.next_subscription);
+        self.next_subscription += 1;
+        self.observers.push((subscription, Box::new(observer)));
+        subscription
+    }
+
+    /// Removes an observer. Returns `false` if it was already removed.
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        let len = self.observers.len();
+        self.observers.retain(|&(s, _)| s != subscription);
+        self.observers.len() != len
+    }
+
+    /// Appends an element to the back of the collection,
+    /// or replaces an element with the same name if one exists.
+    pub fn push(&mut self, value: T) {
+        let index = self.named_vec.len();
+        self.insert_or_replace(index, value);
+    }
+
+    /// Inserts an element at position `index` (shifting all elements after it to the right),
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.insert_or_replace(index, value);
+    }
+
+    /// Removes and returns the element specified by `lookup`.
+    ///
+    /// See [`NamedVec::remove()`](../struct.NamedVec.html#method.remove) for more information.
+    pub fn remove<'a, A>(&mut self, lookup: A) -> T where A: 'a + Into<Lookup<'a>> {
+        let index = self.named_vec.index_from_lookup(lookup.into()).expect("invalid lookup");
+        let item = self.named_vec.remove(index);
+        notify(&mut self.observers, &Change::Removed { index, item: &item });
+        item
+    }
+
+    /// Swaps two elements.
+    ///
+    /// Observers see this as two [`Moved`](enum.Change.html#variant.Moved) changes.
+    ///
+    /// See [`NamedVec::swap()`](../struct.NamedVec.html#method.swap) for more information.
+    pub fn swap<'a, 'b, A, B>(&mut self, first: A, second: B)
+    where A: 'a + Into<Lookup<'a>>, B: 'b + Into<Lookup<'b>> {
+        let a = self.named_vec.index_from_lookup(first.into()).expect("invalid lookup");
+        let b = self.named_vec.index_from_lookup(second.into()).expect("invalid lookup");
+        self.named_vec.swap(a, b);
+        if a != b {
+            notify(&mut self.observers, &Change::Moved { from: a, to: b });
+            notify(&mut self.observers, &Change::Moved { from: b, to: a });
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the rest.
+    ///
+    /// Observers are told about each dropped element, starting from the back,
+    /// unless `len` is 0, in which case they only see [`Cleared`](enum.Change.html#variant.Cleared).
+    pub fn truncate(&mut self, len: usize) {
+        if len == 0 {
+            self.clear();
+        } else {
+            while self.named_vec.len() > len {
+                self.pop();
+            }
+        }
+    }
+
+    /// Removes the last element from the vector and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let item = self.named_vec.pop()?;
+        let index = self.named_vec.len();
+        notify(&mut self.observers, &Change::Removed { index, item: &item });
+        Some(item)
+    }
+
+    /// Clears the vector, removing all values.
+    pub fn clear(&mut self) {
+        if !self.named_vec.is_empty() {
+            self.named_vec.clear();
+            notify(&mut self.observers, &Change::Cleared);
+        }
+    }
+
+    fn insert_or_replace(&mut self, index: usize, value: T) {
+        match self.named_vec.position(value.name()) {
+            Some(i) => {
+                let old = mem::replace(&mut self.named_vec.items[i], value);
+                notify(&mut self.observers, &Change::Replaced { index: i, old: &old });
+            },
+            None => {
+                self.named_vec.insert(index, value);
+                let name = self.named_vec.items[index].name();
+                notify(&mut self.observers, &Change::Inserted { index, name });
+            },
+        }
+    }
+}
+
+fn notify<T>(observers: &mut [(Subscription, Observer<T>)], change: &Change<T>) {
+    for &mut (_, ref mut observer) in observers {
+        observer(change);
+    }
+}
+
+impl<T: Named> Default for Observed<T> {
+    fn default() -> Self {
+        Observed::new()
+    }
+}
+
+impl<T: Named> From<NamedVec<T>> for Observed<T> {
+    fn from(named_vec: NamedVec<T>) -> Self {
+        Observed {
+            named_vec,
+            observers: Vec::new(),
+            next_subscription: 0,
+        }
+    }
+}
+
+impl<T: Named> Deref for Observed<T> {
+    type Target = NamedVec<T>;
+
+    fn deref(&self) -> &NamedVec<T> {
+        &self.named_vec
+    }
+}
+
+impl<T: Named + fmt::Debug> fmt::Debug for Observed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Observed")
+            .field("named_vec", &self.named_vec)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
@@ -0,0 +1,67 @@
+use alloc::vec::Vec;
+
+use rayon::prelude::*;
+
+use {Named, NamedVec};
+
+impl<T: Named + Send> IntoParallelIterator for NamedVec<T> {
+    type Item = T;
+    type Iter = rayon::vec::IntoIter<T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.items.into_par_iter()
+    }
+}
+
+impl<'a, T: Named + Sync> IntoParallelIterator for &'a NamedVec<T> {
+    type Item = &'a T;
+    type Iter = rayon::slice::Iter<'a, T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.items.par_iter()
+    }
+}
+
+impl<'a, T: Named + Send> IntoParallelIterator for &'a mut NamedVec<T> {
+    type Item = &'a mut T;
+    type Iter = rayon::slice::IterMut<'a, T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.items.par_iter_mut()
+    }
+}
+
+impl<T: Named + Send + Sync> FromParallelIterator<T> for NamedVec<T> {
+    /// Collects elements in parallel.
+    ///
+    /// The result is the same as collecting the elements sequentially: an element whose name
+    /// has already been seen replaces the earlier one in its position.
+    fn from_par_iter<I>(par_iter: I) -> Self where I: IntoParallelIterator<Item = T> {
+        let items: Vec<T> = par_iter.into_par_iter().collect();
+
+        // A stable sort keeps each name's elements in input order
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.par_sort_by(|&a, &b| items[a].name().cmp(items[b].name()));
+
+        // Where each element ends up: the first of its name's positions, or none for
+        // every element that is replaced by a later one
+        let mut target: Vec<Option<usize>> = (0..items.len()).map(Some).collect();
+        for group in order.chunk_by(|&a, &b| items[a].name() == items[b].name()) {
+            if let Some((&last, rest)) = group.split_last() {
+                let first = group[0];
+                for &i in rest {
+                    target[i] = None;
+                }
+                target[last] = Some(first);
+            }
+        }
+
+        let mut slots: Vec<Option<T>> = items.iter().map(|_| None).collect();
+        for (item, target) in items.into_iter().zip(target) {
+            if let Some(i) = target {
+                slots[i] = Some(item);
+            }
+        }
+        NamedVec::from_box(slots.into_iter().flatten().collect())
+    }
+}
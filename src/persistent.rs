@@ -0,0 +1,223 @@
+use im::{HashMap, Vector};
+
+use {Lookup, Named, NamedVec};
+
+// Gap left between the order keys of consecutive elements, so that most inserts
+// can pick a key between their neighbours
+const KEY_STRIDE: u64 = 1 << 32;
+
+/// Immutable `NamedVec` whose updates share structure with previous versions.
+///
+/// Every mutating method returns a new `PersistentNamedVec<T>` and leaves `self` untouched,
+/// so keeping old versions around (e.g. for undo) is cheap. Internally, the items are
+/// stored in an RRB vector and the name index in a hash array mapped trie.
+///
+/// The name index maps each name to an order key rather than a position, and the keys
+/// increase along the vector, so a name's position is found by binary search. This lets
+/// inserts and removes in the middle leave the index entries of other elements (and the
+/// structure shared with older versions) alone.
+///
+/// Like `NamedVec`, elements are looked up with a `usize` index or a `&str` name.
+/// `Handle`s are not supported; looking up a `Handle` always returns `None`.
+#[derive(Debug, Clone)]
+pub struct PersistentNamedVec<T: Named + Clone> {
+    map: HashMap<String, u64>,
+    items: Vector<T>,
+    // Order key of each element in `items`, kept in the same order
+    keys: Vector<u64>,
+}
+
+impl<T: Named + Clone> PersistentNamedVec<T> {
+    /// Creates an empty `PersistentNamedVec<T>`.
+    pub fn new() -> Self {
+        PersistentNamedVec {
+            map: HashMap::new(),
+            items: Vector::new(),
+            keys: Vector::new(),
+        }
+    }
+
+    /// Returns the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns a reference to an element.
+    ///
+    /// See [`NamedVec::get()`](struct.NamedVec.html#method.get) for more information.
+    pub fn get<'a, A>(&self, lookup: A) -> Option<&T> where A: 'a + Into<Lookup<'a>> {
+        self.index_from_lookup(lookup.into()).and_then(|i| self.items.get(i))
+    }
+
+    /// Returns an iterator over the elements in order.
+    pub fn iter(&self) -> im::vector::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// Returns a new version with an element appended to the back,
+    /// or with the element of the same name replaced if one exists.
+    pub fn push(&self, value: T) -> Self {
+        let mut new = self.clone();
+        let index = new.len();
+        new.insert_mut(index, value);
+        new
+    }
+
+    /// Returns a new version with an element inserted at position `index`,
+    /// or with the element of the same name replaced if one exists.
+    ///
+    /// This only adds one entry to the name index. Occasionally, when there is no room left
+    /// between the order keys of its neighbours, every key is reassigned, which is a linear
+    /// operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn insert(&self, index: usize, value: T) -> Self {
+        let mut new = self.clone();
+        new.insert_mut(index, value);
+        new
+    }
+
+    /// Returns a new version with the element specified by `lookup` removed.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if a `usize` argument is out of bounds.
+    /// * Panics if a `&str` argument is an invalid name.
+    /// * Panics if given a `Handle`.
+    pub fn remove<'a, A>(&self, lookup: A) -> Self where A: 'a + Into<Lookup<'a>> {
+        let index = self.index_from_lookup(lookup.into()).expect("invalid lookup");
+
+        let mut new = self.clone();
+        let removed = new.items.remove(index);
+        new.keys.remove(index);
+        new.map.remove(removed.name());
+        new
+    }
+
+    /// Returns a new version with the element specified by `lookup` replaced by `value`.
+    ///
+    /// If `value` has a different name than the element it replaces, the old name is freed.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if a `usize` argument is out of bounds.
+    /// * Panics if a `&str` argument is an invalid name.
+    /// * Panics if given a `Handle`.
+    /// * Panics if `value`'s name is in use by a different element.
+    pub fn set<'a, A>(&self, lookup: A, value: T) -> Self where A: 'a + Into<Lookup<'a>> {
+        let index = self.index_from_lookup(lookup.into()).expect("invalid lookup");
+        let old_name = self.items[index].name();
+
+        let mut new = self.clone();
+        if old_name != value.name() {
+            if new.map.contains_key(value.name()) {
+                panic!("name {:?} is already in use", value.name());
+            }
+            new.map.remove(old_name);
+            new.map.insert(value.name().to_owned(), self.keys[index]);
+        }
+        new.items.set(index, value);
+        new
+    }
+
+    fn insert_mut(&mut self, index: usize, value: T) {
+        if let Some(i) = self.position(value.name()) {
+            self.items.set(i, value);
+            return;
+        }
+
+        assert!(index <= self.len(), "insertion index is out of bounds");
+        let key = match self.key_before(index) {
+            Some(key) => key,
+            None => {
+                self.reassign_keys();
+                self.key_before(index).expect("no room for an order key")
+            },
+        };
+        self.map.insert(value.name().to_owned(), key);
+        self.items.insert(index, value);
+        self.keys.insert(index, key);
+    }
+
+    // Picks a key between those of the elements at `index - 1` and `index`, if there is one
+    fn key_before(&self, index: usize) -> Option<u64> {
+        let low = if index == 0 { 0 } else { self.keys[index - 1] };
+        match self.keys.get(index) {
+            Some(&high) if high - low >= 2 => Some(low + (high - low) / 2),
+            Some(_) => None,
+            None => low.checked_add(KEY_STRIDE),
+        }
+    }
+
+    // Spreads the order keys out evenly, which rewrites the whole name index
+    fn reassign_keys(&mut self) {
+        let stride = KEY_STRIDE.min(u64::MAX / (self.len() as u64 + 2));
+        self.keys = (1..=self.len() as u64).map(|i| i * stride).collect();
+        self.map = self.items.iter()
+            .zip(self.keys.iter())
+            .map(|(item, &key)| (item.name().to_owned(), key))
+            .collect();
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        let key = self.map.get(name)?;
+        self.keys.binary_search(key).ok()
+    }
+
+    fn index_from_lookup(&self, lookup: Lookup) -> Option<usize> {
+        match lookup {
+            Lookup::Name(name) => {
+                self.position(name)
+            },
+            Lookup::Index(index) => {
+                if index < self.items.len() {
+                    Some(index)
+                } else {
+                    None
+                }
+            },
+            Lookup::Handle(_) => {
+                None
+            },
+        }
+    }
+}
+
+impl<T: Named + Clone> Default for PersistentNamedVec<T> {
+    fn default() -> Self {
+        PersistentNamedVec::new()
+    }
+}
+
+impl<T: Named + Clone + PartialEq> PartialEq for PersistentNamedVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<T: Named + Clone> From<NamedVec<T>> for PersistentNamedVec<T> {
+    fn from(named_vec: NamedVec<T>) -> Self {
+        let mut new = PersistentNamedVec::new();
+        for item in named_vec {
+            let index = new.len();
+            new.insert_mut(index, item);
+        }
+        new
+    }
+}
+
+impl<'a, T: Named + Clone> IntoIterator for &'a PersistentNamedVec<T> {
+    type Item = &'a T;
+    type IntoIter = im::vector::Iter<'a, T>;
+
+    fn into_iter(self) -> im::vector::Iter<'a, T> {
+        self.items.iter()
+    }
+}
@@ -0,0 +1,135 @@
+use core::fmt;
+use core::ops::{Bound, Deref, RangeBounds};
+
+use {Lookup, Named, NamedVec};
+
+/// `NamedVec` that keeps its elements sorted by a key.
+///
+/// The key of each element is computed by a function or closure given to
+/// [`new()`](#method.new).
+/// [`push()`](#method.push) places each element after every element with a smaller or
+/// equal key, so elements with equal keys stay in the order they were pushed.
+///
+/// `SortedNamedVec<T, F>` dereferences to `NamedVec<T>` for reading, so names are still
+/// looked up in O(1). There is no `insert()` or `get_mut()`, since either could break the
+/// ordering; to change an element, push a replacement with the same name.
+#[derive(Clone)]
+pub struct SortedNamedVec<T: Named, F> {
+    named_vec: NamedVec<T>,
+    key: F,
+}
+
+impl<T: Named, K: Ord, F: Fn(&T) -> K> SortedNamedVec<T, F> {
+    /// Creates an empty `SortedNamedVec<T, F>` ordered by `key`.
+    pub fn new(key: F) -> Self {
+        SortedNamedVec {
+            named_vec: NamedVec::new(),
+            key,
+        }
+    }
+
+    /// Inserts an element at the position given by its key,
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// A replaced element is moved if the new element's key sorts elsewhere.
+    pub fn push(&mut self, value: T) {
+        if let Some(i) = self.named_vec.position(value.name()) {
+            self.named_vec.remove(i);
+        }
+        let key = (self.key)(&value);
+        let index = self.named_vec.items.partition_point(|item| (self.key)(item) <= key);
+        self.named_vec.insert(index, value);
+    }
+
+    /// Removes and returns the element specified by `lookup`.
+    ///
+    /// See [`NamedVec::remove()`](struct.NamedVec.html#method.remove) for more information.
+    pub fn remove<'a, A>(&mut self, lookup: A) -> T where A: 'a + Into<Lookup<'a>> + Copy {
+        self.named_vec.remove(lookup)
+    }
+
+    /// Clears the vector, removing all values.
+    pub fn clear(&mut self) {
+        self.named_vec.clear();
+    }
+
+    /// Returns the element with the smallest key, or `None` if the vector is empty.
+    pub fn first(&self) -> Option<&T> {
+        self.named_vec.items.first()
+    }
+
+    /// Returns the element with the largest key, or `None` if the vector is empty.
+    pub fn last(&self) -> Option<&T> {
+        self.named_vec.items.last()
+    }
+
+    /// Removes and returns the element with the smallest key, or `None` if the vector is empty.
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.named_vec.is_empty() {
+            None
+        } else {
+            Some(self.named_vec.remove(0))
+        }
+    }
+
+    /// Removes and returns the element with the largest key, or `None` if the vector is empty.
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.named_vec.pop()
+    }
+
+    /// Returns the position of the element named `name` in key order,
+    /// or `None` if there is no such element.
+    pub fn rank(&self, name: &str) -> Option<usize> {
+        self.named_vec.position(name)
+    }
+
+    /// Binary searches for an element with key `key`.
+    ///
+    /// Returns `Ok` with the index of a matching element, or `Err` with the index where an
+    /// element with that key would be inserted. If several elements match, any of them
+    /// may be returned.
+    pub fn binary_search_by_key(&self, key: &K) -> Result<usize, usize> {
+        self.named_vec.items.binary_search_by(|item| (self.key)(item).cmp(key))
+    }
+
+    /// Returns the elements whose keys are within `range`, in order.
+    pub fn range_by_key<R: RangeBounds<K>>(&self, range: R) -> &[T] {
+        let items = &self.named_vec.items;
+        let start = match range.start_bound() {
+            Bound::Included(start) => items.partition_point(|item| (self.key)(item) < *start),
+            Bound::Excluded(start) => items.partition_point(|item| (self.key)(item) <= *start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => items.partition_point(|item| (self.key)(item) <= *end),
+            Bound::Excluded(end) => items.partition_point(|item| (self.key)(item) < *end),
+            Bound::Unbounded => items.len(),
+        };
+        &items[start..end.max(start)]
+    }
+
+    /// Returns the underlying `NamedVec<T>`.
+    pub fn into_inner(self) -> NamedVec<T> {
+        self.named_vec
+    }
+}
+
+impl<T: Named, F> Deref for SortedNamedVec<T, F> {
+    type Target = NamedVec<T>;
+
+    fn deref(&self) -> &NamedVec<T> {
+        &self.named_vec
+    }
+}
+
+impl<T: Named + fmt::Debug, F> fmt::Debug for SortedNamedVec<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.named_vec.fmt(f)
+    }
+}
+
+impl<T: Named + PartialEq, F> PartialEq for SortedNamedVec<T, F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.named_vec == other.named_vec
+    }
+}
@@ -0,0 +1,68 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use {Named, NamedVec};
+
+impl<T: Named> NamedVec<T> {
+    /// Returns an adapter that displays the vector as a text table, for command-line output.
+    ///
+    /// The table has a column for each element's index and name, followed by `columns`,
+    /// whose values for each element are returned by `fields`. Columns are left-aligned
+    /// and separated by two spaces, and the header is underlined with dashes.
+    pub fn display_table<'a, F>(&'a self, columns: &'a [&'a str], fields: F) -> DisplayTable<'a, T, F>
+    where F: Fn(&T) -> Vec<String> {
+        DisplayTable {
+            named_vec: self,
+            columns,
+            fields,
+        }
+    }
+}
+
+/// Text table returned by [`NamedVec::display_table()`](struct.NamedVec.html#method.display_table).
+pub struct DisplayTable<'a, T: Named + 'a, F> {
+    named_vec: &'a NamedVec<T>,
+    columns: &'a [&'a str],
+    fields: F,
+}
+
+impl<'a, T: Named, F> fmt::Display for DisplayTable<'a, T, F> where F: Fn(&T) -> Vec<String> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows: Vec<Vec<String>> = Vec::with_capacity(self.named_vec.len() + 1);
+        let mut header = alloc::vec![String::from("#"), String::from("name")];
+        header.extend(self.columns.iter().map(|&column| column.into()));
+        rows.push(header);
+        for (i, item) in self.named_vec.items.iter().enumerate() {
+            let mut row = alloc::vec![i.to_string(), item.name().into()];
+            row.extend((self.fields)(item));
+            rows.push(row);
+        }
+
+        let mut widths = Vec::new();
+        for row in &rows {
+            for (i, field) in row.iter().enumerate() {
+                let width = field.chars().count();
+                if i == widths.len() {
+                    widths.push(width);
+                } else if width > widths[i] {
+                    widths[i] = width;
+                }
+            }
+        }
+
+        let rule = widths.iter().map(|&width| "-".repeat(width)).collect();
+        rows.insert(1, rule);
+        for row in &rows {
+            for (i, field) in row.iter().enumerate() {
+                if i + 1 == row.len() {
+                    f.write_str(field)?;
+                } else {
+                    write!(f, "{:width$}  ", field, width = widths[i])?;
+                }
+            }
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
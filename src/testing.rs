@@ -0,0 +1,245 @@
+//! Helpers for property testing code that uses `NamedVec`.
+//!
+//! [`Op`](enum.Op.html) describes a single mutation, and [`check_model()`](fn.check_model.html)
+//! replays a sequence of them against both a `NamedVec` and a naive `Vec` model,
+//! reporting the first step where the two disagree.
+//!
+//! Random `NamedVec`s and `Op`s can be generated with any of the optional `proptest`,
+//! `quickcheck` and `arbitrary` features. Generated `NamedVec`s always have unique names.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use {Named, NamedVec};
+
+/// A mutation of a `NamedVec`.
+///
+/// Indices are taken modulo the length of the vector they are applied to (plus one for
+/// [`Insert`](#variant.Insert) and [`Truncate`](#variant.Truncate)), so any `Op` can be
+/// applied to any vector. Operations that need an element do nothing on an empty vector.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Op<T> {
+    Push(T),
+    Insert(usize, T),
+    Remove(usize),
+    Swap(usize, usize),
+    Pop,
+    Truncate(usize),
+}
+
+impl<T: Named + Clone> Op<T> {
+    /// Applies this operation to `named_vec`, returning the element it removed, if any.
+    pub fn apply(&self, named_vec: &mut NamedVec<T>) -> Option<T> {
+        let len = named_vec.len();
+        match *self {
+            Op::Push(ref item) => named_vec.push(item.clone()),
+            Op::Insert(index, ref item) => named_vec.insert(index % (len + 1), item.clone()),
+            Op::Remove(index) if len > 0 => return Some(named_vec.remove(index % len)),
+            Op::Swap(a, b) if len > 0 => named_vec.swap(a % len, b % len),
+            Op::Pop => return named_vec.pop(),
+            Op::Truncate(new_len) => named_vec.truncate(new_len % (len + 1)),
+            Op::Remove(_) | Op::Swap(..) => {},
+        }
+        None
+    }
+
+    // Same as `apply()`, on a `Vec` that finds names by scanning
+    fn apply_to_model(&self, model: &mut Vec<T>) -> Option<T> {
+        let len = model.len();
+        let insert = |model: &mut Vec<T>, index: usize, item: &T| {
+            match model.iter().position(|x| x.name() == item.name()) {
+                Some(i) => model[i] = item.clone(),
+                None => model.insert(index, item.clone()),
+            }
+        };
+
+        match *self {
+            Op::Push(ref item) => insert(model, len, item),
+            Op::Insert(index, ref item) => insert(model, index % (len + 1), item),
+            Op::Remove(index) if len > 0 => return Some(model.remove(index % len)),
+            Op::Swap(a, b) if len > 0 => model.swap(a % len, b % len),
+            Op::Pop => return model.pop(),
+            Op::Truncate(new_len) => model.truncate(new_len % (len + 1)),
+            Op::Remove(_) | Op::Swap(..) => {},
+        }
+        None
+    }
+}
+
+/// The first step at which a `NamedVec` disagreed with the model, as reported by
+/// [`check_model()`](fn.check_model.html).
+#[derive(Debug, PartialEq, Clone)]
+pub struct Mismatch<T> {
+    /// Position of the operation in the sequence.
+    pub step: usize,
+    pub op: Op<T>,
+    pub reason: &'static str,
+}
+
+impl<T: fmt::Debug> fmt::Display for Mismatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "step {} ({:?}): {}", self.step, self.op, self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for Mismatch<T> {}
+
+/// Applies `ops` to a clone of `initial` and to a `Vec` model of it, checking after each step
+/// that both hold the same elements, that every name is found at the right index
+/// and that [`check_invariants()`](../struct.NamedVec.html#method.check_invariants) passes.
+pub fn check_model<T>(initial: &NamedVec<T>, ops: &[Op<T>]) -> Result<(), Mismatch<T>>
+where T: Named + Clone + PartialEq {
+    let mut named_vec = initial.clone();
+    let mut model = initial.items.clone();
+
+    for (step, op) in ops.iter().enumerate() {
+        let mismatch = |reason| Mismatch { step, op: op.clone(), reason };
+
+        if op.apply(&mut named_vec) != op.apply_to_model(&mut model) {
+            return Err(mismatch("removed a different element"));
+        }
+        if named_vec.items != model {
+            return Err(mismatch("elements differ"));
+        }
+        for (i, item) in model.iter().enumerate() {
+            if named_vec.index_from_lookup(item.name().into()) != Some(i) {
+                return Err(mismatch("name lookup returned the wrong index"));
+            }
+        }
+        if named_vec.check_invariants().is_err() {
+            return Err(mismatch("invariants do not hold"));
+        }
+    }
+
+    Ok(())
+}
+
+//////////////
+// proptest //
+//////////////
+
+/// [`proptest`](https://docs.rs/proptest) strategies.
+#[cfg(feature = "proptest")]
+pub mod strategy {
+    use core::fmt;
+    use core::ops::Range;
+
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use super::Op;
+    use {Named, NamedVec};
+
+    /// Generates `NamedVec`s of elements from `element`.
+    ///
+    /// Each `NamedVec` is built by pushing up to `size.end - 1` generated elements,
+    /// so elements with repeated names replace earlier ones and the result may be
+    /// shorter than `size.start`.
+    pub fn named_vec<T, S>(element: S, size: Range<usize>) -> impl Strategy<Value = NamedVec<T>>
+    where T: Named + fmt::Debug, S: Strategy<Value = T> {
+        vec(element, size).prop_map(|items| {
+            let mut named_vec = NamedVec::new();
+            for item in items {
+                named_vec.push(item);
+            }
+            named_vec
+        })
+    }
+
+    /// Generates `Op`s, using `element` for the elements that are pushed or inserted.
+    pub fn op<T, S>(element: S) -> impl Strategy<Value = Op<T>>
+    where T: fmt::Debug + Clone, S: Strategy<Value = T> + Clone {
+        prop_oneof![
+            element.clone().prop_map(Op::Push),
+            (any::<usize>(), element).prop_map(|(index, item)| Op::Insert(index, item)),
+            any::<usize>().prop_map(Op::Remove),
+            (any::<usize>(), any::<usize>()).prop_map(|(a, b)| Op::Swap(a, b)),
+            Just(Op::Pop),
+            any::<usize>().prop_map(Op::Truncate),
+        ]
+    }
+}
+
+////////////////
+// quickcheck //
+////////////////
+
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impls {
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+
+    use quickcheck::{Arbitrary, Gen};
+
+    use super::Op;
+    use {Named, NamedVec};
+
+    // Pushing replaces elements with repeated names, so the result is always well-formed
+    fn from_items<T: Named>(items: Vec<T>) -> NamedVec<T> {
+        let mut named_vec = NamedVec::new();
+        for item in items {
+            named_vec.push(item);
+        }
+        named_vec
+    }
+
+    impl<T: Named + Arbitrary> Arbitrary for NamedVec<T> {
+        fn arbitrary(g: &mut Gen) -> Self {
+            from_items(Vec::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            Box::new(self.items.shrink().map(from_items))
+        }
+    }
+
+    impl<T: Arbitrary> Arbitrary for Op<T> {
+        fn arbitrary(g: &mut Gen) -> Self {
+            match u8::arbitrary(g) % 6 {
+                0 => Op::Push(T::arbitrary(g)),
+                1 => Op::Insert(usize::arbitrary(g), T::arbitrary(g)),
+                2 => Op::Remove(usize::arbitrary(g)),
+                3 => Op::Swap(usize::arbitrary(g), usize::arbitrary(g)),
+                4 => Op::Pop,
+                _ => Op::Truncate(usize::arbitrary(g)),
+            }
+        }
+    }
+}
+
+///////////////
+// arbitrary //
+///////////////
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls {
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    use super::Op;
+    use {Named, NamedVec};
+
+    impl<'a, T: Named + Arbitrary<'a>> Arbitrary<'a> for NamedVec<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            // Pushing replaces elements with repeated names, so the result is always well-formed
+            let mut named_vec = NamedVec::new();
+            for item in u.arbitrary_iter()? {
+                named_vec.push(item?);
+            }
+            Ok(named_vec)
+        }
+    }
+
+    impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Op<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(match u.int_in_range(0..=5u8)? {
+                0 => Op::Push(u.arbitrary()?),
+                1 => Op::Insert(u.arbitrary()?, u.arbitrary()?),
+                2 => Op::Remove(u.arbitrary()?),
+                3 => Op::Swap(u.arbitrary()?, u.arbitrary()?),
+                4 => Op::Pop,
+                _ => Op::Truncate(u.arbitrary()?),
+            })
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use alloc::vec::Vec;
+use core::mem;
+use core::ops::Deref;
+
+use {Lookup, LookupError, Named, NamedVec};
+
+impl<T: Named> NamedVec<T> {
+    /// Applies several changes as a single unit.
+    ///
+    /// `f` receives a [`Transaction`](struct.Transaction.html), whose mutators return a
+    /// [`LookupError`](enum.LookupError.html) instead of panicking. If `f` returns `Err`
+    /// or panics, every change it made is rolled back, leaving the `NamedVec` exactly as it was.
+    ///
+    /// Lookups by name inside `f` use the name index as usual. Nothing is copied when the
+    /// transaction starts; a rollback undoes each change in turn and rebuilds the index once.
+    pub fn transaction<F, R, E>(&mut self, f: F) -> Result<R, E>
+    where F: FnOnce(&mut Transaction<T>) -> Result<R, E> {
+        let mut transaction = Transaction::new(self);
+        let result = f(&mut transaction);
+        if result.is_ok() {
+            transaction.commit();
+        }
+        result
+    }
+}
+
+// Enough information to undo a change, including its effect on the handle slots
+enum Undo<T> {
+    // Whether the element's slot was reused from `free_slots`
+    Inserted(usize, bool),
+    Removed(usize, T),
+    Replaced(usize, T),
+    Swapped(usize, usize),
+    Truncated(Vec<T>),
+}
+
+/// Mutable view of a `NamedVec` inside [`NamedVec::transaction()`](struct.NamedVec.html#method.transaction).
+///
+/// A `Transaction<T>` dereferences to `NamedVec<T>` for reading.
+pub struct Transaction<'a, T: Named + 'a> {
+    named_vec: &'a mut NamedVec<T>,
+    undo: Vec<Undo<T>>,
+    committed: bool,
+}
+
+impl<'a, T: Named> Transaction<'a, T> {
+    fn new(named_vec: &'a mut NamedVec<T>) -> Self {
+        Transaction {
+            undo: Vec::new(),
+            named_vec,
+            committed: false,
+        }
+    }
+
+    /// Appends an element to the back of the collection,
+    /// or replaces an element with the same name if one exists.
+    pub fn push(&mut self, value: T) {
+        let index = self.named_vec.len();
+        self.insert_or_replace(index, value);
+    }
+
+    /// Inserts an element at position `index` (shifting all elements after it to the right),
+    /// or replaces an element with the same name if one exists.
+    ///
+    /// Returns an error if `index` is out of bounds.
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), LookupError> {
+        if index > self.named_vec.len() && self.named_vec.position(value.name()).is_none() {
+            return Err(LookupError::Index(index));
+        }
+        self.insert_or_replace(index, value);
+        Ok(())
+    }
+
+    /// Removes the element specified by `lookup` and returns a reference to it,
+    /// or returns an error if there is no such element.
+    ///
+    /// The removed element is kept until the transaction ends,
+    /// so that it can be put back if the transaction is rolled back.
+    pub fn remove<'b, A>(&mut self, lookup: A) -> Result<&T, LookupError>
+    where A: 'b + Into<Lookup<'b>> {
+        let index = self.index(lookup.into())?;
+        let item = self.named_vec.remove(index);
+        self.undo.push(Undo::Removed(index, item));
+        match self.undo.last() {
+            Some(Undo::Removed(_, item)) => Ok(item),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns a mutable reference to the element specified by `lookup`,
+    /// or returns an error if there is no such element.
+    ///
+    /// The element is cloned first, so that it can be restored if the transaction is
+    /// rolled back. Its name must not be changed.
+    pub fn get_mut<'b, A>(&mut self, lookup: A) -> Result<&mut T, LookupError>
+    where T: Clone, A: 'b + Into<Lookup<'b>> {
+        let index = self.index(lookup.into())?;
+        self.undo.push(Undo::Replaced(index, self.named_vec.items[index].clone()));
+        Ok(&mut self.named_vec.items[index])
+    }
+
+    /// Swaps two elements, or returns an error if either one does not exist.
+    pub fn swap<'b, 'c, A, B>(&mut self, first: A, second: B) -> Result<(), LookupError>
+    where A: 'b + Into<Lookup<'b>>, B: 'c + Into<Lookup<'c>> {
+        let a = self.index(first.into())?;
+        let b = self.index(second.into())?;
+        self.named_vec.swap(a, b);
+        self.undo.push(Undo::Swapped(a, b));
+        Ok(())
+    }
+
+    /// Removes the last element from the vector and returns a reference to it,
+    /// or `None` if it is empty.
+    ///
+    /// See [`remove()`](#method.remove) for more information.
+    pub fn pop(&mut self) -> Option<&T> {
+        let index = self.named_vec.len().checked_sub(1)?;
+        self.remove(index).ok()
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no effect.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.named_vec.len() {
+            if let Some(ref mut map) = self.named_vec.map {
+                for item in self.named_vec.items[len..].iter() {
+                    map.remove(item.name());
+                }
+            }
+            for slot in self.named_vec.item_slots.split_off(len) {
+                self.named_vec.free_slot(slot);
+            }
+            let removed = self.named_vec.items.split_off(len);
+            self.undo.push(Undo::Truncated(removed));
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    fn index(&self, lookup: Lookup) -> Result<usize, LookupError> {
+        match self.named_vec.index_from_lookup(lookup) {
+            Some(index) if index < self.named_vec.len() => Ok(index),
+            _ => Err(LookupError::from(lookup)),
+        }
+    }
+
+    fn insert_or_replace(&mut self, index: usize, value: T) {
+        match self.named_vec.position(value.name()) {
+            Some(i) => {
+                let old = mem::replace(&mut self.named_vec.items[i], value);
+                self.undo.push(Undo::Replaced(i, old));
+            },
+            None => {
+                let reused_slot = !self.named_vec.free_slots.is_empty();
+                self.named_vec.insert(index, value);
+                self.undo.push(Undo::Inserted(index, reused_slot));
+            },
+        }
+    }
+
+    fn commit(mut self) {
+        self.committed = true;
+    }
+
+    // Undoes each change in reverse, so that freed slots come back off `free_slots`
+    // in the order they went on
+    fn rollback(&mut self) {
+        if self.undo.is_empty() {
+            return;
+        }
+
+        let named_vec = &mut *self.named_vec;
+        while let Some(undo) = self.undo.pop() {
+            match undo {
+                Undo::Inserted(index, reused_slot) => {
+                    named_vec.items.remove(index);
+                    let slot = named_vec.item_slots.remove(index);
+                    named_vec.slots[slot].index = None;
+                    if reused_slot {
+                        named_vec.free_slots.push(slot);
+                    } else {
+                        named_vec.slots.pop();
+                    }
+                    named_vec.reindex_slots(index);
+                },
+                Undo::Removed(index, item) => {
+                    named_vec.items.insert(index, item);
+                    let slot = named_vec.free_slots.pop().unwrap();
+                    named_vec.slots[slot].generation -= 1;
+                    named_vec.item_slots.insert(index, slot);
+                    named_vec.reindex_slots(index);
+                },
+                Undo::Replaced(index, item) => {
+                    named_vec.items[index] = item;
+                },
+                Undo::Swapped(a, b) => {
+                    named_vec.items.swap(a, b);
+                    named_vec.item_slots.swap(a, b);
+                    named_vec.slots[named_vec.item_slots[a]].index = Some(a);
+                    named_vec.slots[named_vec.item_slots[b]].index = Some(b);
+                },
+                Undo::Truncated(removed) => {
+                    let len = named_vec.items.len();
+                    let freed = named_vec.free_slots.len() - removed.len();
+                    for slot in named_vec.free_slots.drain(freed..) {
+                        named_vec.slots[slot].generation -= 1;
+                        named_vec.item_slots.push(slot);
+                    }
+                    named_vec.items.extend(removed);
+                    named_vec.reindex_slots(len);
+                },
+            }
+        }
+
+        if named_vec.map.is_some() {
+            named_vec.build_index();
+        }
+    }
+}
+
+impl<'a, T: Named> Drop for Transaction<'a, T> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.rollback();
+        }
+    }
+}
+
+impl<'a, T: Named> Deref for Transaction<'a, T> {
+    type Target = NamedVec<T>;
+
+    fn deref(&self) -> &NamedVec<T> {
+        self.named_vec
+    }
+}
@@ -0,0 +1,67 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn push_and_get() {
+    let mut array: ArrayNamedVec<NamedNumber, 4> = ArrayNamedVec::new();
+    array.push(NamedNumber::new("foo", 0));
+    array.push(NamedNumber::new("bar", 1));
+    array.push(NamedNumber::new("foo", 2));
+
+    assert_eq!(array.len(), 2);
+    assert_eq!(array["foo"].num(), 2);
+    assert_eq!(array.get(1).unwrap().num(), 1);
+    assert!(array.get("baz").is_none());
+}
+
+#[test]
+fn try_push_when_full() {
+    let mut array: ArrayNamedVec<NamedNumber, 2> = ArrayNamedVec::new();
+    array.push(NamedNumber::new("foo", 0));
+    array.push(NamedNumber::new("bar", 1));
+
+    assert_eq!(array.try_push(NamedNumber::new("baz", 2)), Err(NamedNumber::new("baz", 2)));
+    assert_eq!(array.try_push(NamedNumber::new("foo", 3)), Ok(()));
+    assert_eq!(array["foo"].num(), 3);
+}
+
+#[test]
+#[should_panic]
+fn push_when_full() {
+    let mut array: ArrayNamedVec<NamedNumber, 1> = ArrayNamedVec::new();
+    array.push(NamedNumber::new("foo", 0));
+    array.push(NamedNumber::new("bar", 1));
+}
+
+#[test]
+fn insert_remove_and_swap() {
+    let mut array: ArrayNamedVec<NamedNumber, 8> = ArrayNamedVec::new();
+    for (i, name) in ["a", "b", "c", "d", "e", "f", "g"].iter().enumerate() {
+        array.push(NamedNumber::new(name, i as i32));
+    }
+
+    array.insert(0, NamedNumber::new("h", 7));
+    assert_eq!(array.remove("c").num(), 2);
+    assert_eq!(array.remove(0).num(), 7);
+    array.swap("a", "g");
+
+    let names: Vec<_> = array.iter().map(|n| n.name().to_owned()).collect();
+    assert_eq!(names, ["g", "b", "d", "e", "f", "a"]);
+    for (i, name) in names.iter().enumerate() {
+        assert_eq!(array.get(name.as_str()), array.get(i));
+    }
+}
+
+#[test]
+fn pop_and_into_iter() {
+    let mut array: ArrayNamedVec<NamedNumber, 3> = ArrayNamedVec::new();
+    array.push(NamedNumber::new("foo", 0));
+    array.push(NamedNumber::new("bar", 1));
+
+    assert_eq!(array.pop(), Some(NamedNumber::new("bar", 1)));
+    assert!(array.get("bar").is_none());
+    assert_eq!(array.into_iter().collect::<Vec<_>>(), [NamedNumber::new("foo", 0)]);
+}
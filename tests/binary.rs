@@ -0,0 +1,79 @@
+extern crate named_vec;
+use named_vec::binary::{self, DecodeError, Entry};
+use named_vec::*;
+
+mod common;
+use common::*;
+
+fn encode(named_vec: &NamedVec<NamedNumber>) -> Vec<u8> {
+    binary::encode(named_vec, |n, out| out.extend_from_slice(&n.num().to_le_bytes()))
+}
+
+fn decode(entry: Entry) -> Result<NamedNumber, &'static str> {
+    if entry.data().len() != 4 {
+        return Err("bad length");
+    }
+    let mut buf = [0; 4];
+    buf.copy_from_slice(entry.data());
+    Ok(NamedNumber::new(entry.name(), i32::from_le_bytes(buf)))
+}
+
+#[test]
+fn round_trip() {
+    for &count in &[0, 1, 5, 100] {
+        let named_vec = numbers(count);
+        let bytes = encode(&named_vec);
+        let named_vec_ref = NamedVecRef::new(&bytes).unwrap();
+        assert_eq!(named_vec_ref.len(), count as usize);
+        assert_eq!(named_vec_ref.to_named_vec(decode), Ok(named_vec));
+    }
+}
+
+#[test]
+fn lookup_without_decoding() {
+    let bytes = encode(&numbers(50));
+    let named_vec_ref = NamedVecRef::new(&bytes).unwrap();
+
+    assert_eq!(named_vec_ref.get("n42").unwrap().data(), &42i32.to_le_bytes());
+    assert_eq!(named_vec_ref.get(7).unwrap().name(), "n7");
+    assert!(named_vec_ref.get("n50").is_none());
+    assert!(named_vec_ref.get(50).is_none());
+
+    let names: Vec<_> = named_vec_ref.iter().map(|e| e.name()).take(3).collect();
+    assert_eq!(names, ["n0", "n1", "n2"]);
+}
+
+#[test]
+fn invalid_input() {
+    let mut bytes = encode(&numbers(3));
+
+    assert_eq!(NamedVecRef::new(b"JSON{}").unwrap_err(), DecodeError::BadMagic);
+    match NamedVecRef::new(&bytes[..bytes.len() - 1]) {
+        Err(DecodeError::Truncated { .. }) => {},
+        other => panic!("unexpected {:?}", other),
+    }
+
+    bytes[4] = 2;
+    assert_eq!(NamedVecRef::new(&bytes).unwrap_err(), DecodeError::UnsupportedVersion(2));
+    bytes[4] = 1;
+
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    match NamedVecRef::new(&bytes) {
+        Err(DecodeError::ChecksumMismatch { .. }) => {},
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn huge_header_sizes() {
+    let mut bytes = encode(&numbers(3));
+    for b in &mut bytes[8..20] {
+        *b = 0xff;
+    }
+
+    match NamedVecRef::new(&bytes) {
+        Err(DecodeError::Truncated { .. }) | Err(DecodeError::Corrupt(_)) => {},
+        other => panic!("unexpected {:?}", other),
+    }
+}
@@ -1,7 +1,7 @@
 extern crate named_vec;
-use named_vec::Named;
+use named_vec::{Named, NamedVec};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
 pub struct NamedNumber {
     name: String,
     num: i32,
@@ -11,7 +11,7 @@ impl NamedNumber {
     pub fn new(name: &str, num: i32) -> Self {
         NamedNumber {
             name: name.to_owned(),
-            num: num,
+            num,
         }
     }
 
@@ -27,3 +27,15 @@ impl Named for NamedNumber {
     }
 }
 
+/// Returns `count` elements named `n0`, `n1`, ..., each holding its own index.
+#[allow(dead_code)]
+pub fn numbers(count: i32) -> NamedVec<NamedNumber> {
+    (0..count).map(|i| NamedNumber::new(&format!("n{}", i), i)).collect()
+}
+
+/// Returns the names of `items`, in order.
+#[allow(dead_code)]
+pub fn names<'a, I>(items: I) -> Vec<&'a str> where I: IntoIterator<Item = &'a NamedNumber> {
+    items.into_iter().map(|n| n.name()).collect()
+}
+
@@ -0,0 +1,94 @@
+#![cfg(all(feature = "std", not(loom)))]
+
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn upsert_keeps_order() {
+    let concurrent = ConcurrentNamedVec::new();
+    concurrent.upsert(NamedNumber::new("foo", 0));
+    concurrent.upsert(NamedNumber::new("bar", 1));
+    let replaced = concurrent.upsert(NamedNumber::new("foo", 2));
+
+    let mut expected = NamedVec::new();
+    expected.push(NamedNumber::new("foo", 2));
+    expected.push(NamedNumber::new("bar", 1));
+
+    assert_eq!(replaced, Some(NamedNumber::new("foo", 0)));
+    assert_eq!(concurrent.snapshot(), expected);
+}
+
+#[test]
+fn update_and_remove() {
+    let concurrent = ConcurrentNamedVec::new();
+    concurrent.upsert(NamedNumber::new("foo", 0));
+
+    assert_eq!(concurrent.update("foo", |n| *n = NamedNumber::new("foo", 5)), Ok(Some(())));
+    assert_eq!(concurrent.update("bar", |_| ()), Ok(None));
+    assert_eq!(concurrent.get_cloned("foo").unwrap().num(), 5);
+    assert_eq!(concurrent.remove("foo").unwrap().num(), 5);
+    assert!(concurrent.is_empty());
+}
+
+#[test]
+fn update_cannot_rename() {
+    let concurrent = ConcurrentNamedVec::new();
+    concurrent.upsert(NamedNumber::new("foo", 0));
+
+    let result = concurrent.update("foo", |n| *n = NamedNumber::new("bar", 1));
+    assert_eq!(result, Err(RenameError { expected: "foo".to_owned(), found: "bar".to_owned() }));
+
+    // The element is unchanged and its lock is still usable
+    assert_eq!(concurrent.get_cloned("foo"), Some(NamedNumber::new("foo", 0)));
+    assert_eq!(concurrent.snapshot(), named_vec![NamedNumber::new("foo", 0)]);
+    assert_eq!(concurrent.update("foo", |n| *n = NamedNumber::new("foo", 2)), Ok(Some(())));
+    assert_eq!(concurrent.get_cloned("foo").unwrap().num(), 2);
+}
+
+#[test]
+fn update_survives_panicking_closure() {
+    let concurrent = Arc::new(ConcurrentNamedVec::new());
+    concurrent.upsert(NamedNumber::new("foo", 0));
+
+    let other = concurrent.clone();
+    let result = thread::spawn(move || {
+        other.update("foo", |_| panic!("oops")).unwrap();
+    }).join();
+    assert!(result.is_err());
+
+    assert_eq!(concurrent.get_cloned("foo"), Some(NamedNumber::new("foo", 0)));
+    assert_eq!(concurrent.update("foo", |n| *n = NamedNumber::new("foo", 1)), Ok(Some(())));
+    assert_eq!(concurrent.snapshot(), named_vec![NamedNumber::new("foo", 1)]);
+    assert_eq!(concurrent.remove("foo"), Some(NamedNumber::new("foo", 1)));
+}
+
+#[test]
+fn many_threads() {
+    let concurrent = Arc::new(ConcurrentNamedVec::new());
+    let names: Vec<String> = (0..8).map(|i| format!("item{}", i)).collect();
+
+    let threads: Vec<_> = names.iter().cloned().map(|name| {
+        let concurrent = concurrent.clone();
+        thread::spawn(move || {
+            concurrent.upsert(NamedNumber::new(&name, 0));
+            for _ in 0..100 {
+                concurrent.update(&name, |n| *n = NamedNumber::new(&name, n.num() + 1)).unwrap();
+            }
+        })
+    }).collect();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    for name in &names {
+        assert_eq!(concurrent.get_cloned(name).unwrap().num(), 100);
+    }
+    assert_eq!(concurrent.len(), names.len());
+}
@@ -0,0 +1,69 @@
+//! Model-checked tests for `ConcurrentNamedVec`.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test --release --test concurrent_loom`.
+#![cfg(all(feature = "std", loom))]
+
+extern crate loom;
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn concurrent_upserts_are_all_kept() {
+    loom::model(|| {
+        let concurrent = Arc::new(ConcurrentNamedVec::with_shards(2));
+
+        let other = concurrent.clone();
+        let thread = thread::spawn(move || {
+            other.upsert(NamedNumber::new("foo", 0));
+        });
+        concurrent.upsert(NamedNumber::new("bar", 1));
+        thread.join().unwrap();
+
+        let snapshot = concurrent.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot["foo"].num(), 0);
+        assert_eq!(snapshot["bar"].num(), 1);
+    });
+}
+
+#[test]
+fn update_races_with_upsert() {
+    loom::model(|| {
+        let concurrent = Arc::new(ConcurrentNamedVec::with_shards(1));
+        concurrent.upsert(NamedNumber::new("foo", 0));
+
+        let other = concurrent.clone();
+        let thread = thread::spawn(move || {
+            other.update("foo", |n| *n = NamedNumber::new("foo", n.num() + 1)).unwrap();
+        });
+        concurrent.upsert(NamedNumber::new("foo", 10));
+        thread.join().unwrap();
+
+        let num = concurrent.get_cloned("foo").unwrap().num();
+        assert!(num == 10 || num == 11);
+        assert_eq!(concurrent.len(), 1);
+    });
+}
+
+#[test]
+fn remove_races_with_get() {
+    loom::model(|| {
+        let concurrent = Arc::new(ConcurrentNamedVec::with_shards(1));
+        concurrent.upsert(NamedNumber::new("foo", 0));
+
+        let other = concurrent.clone();
+        let thread = thread::spawn(move || other.get_cloned("foo"));
+        let removed = concurrent.remove("foo");
+
+        let seen = thread.join().unwrap();
+        assert_eq!(removed, Some(NamedNumber::new("foo", 0)));
+        assert!(seen.is_none() || seen == removed);
+        assert!(concurrent.is_empty());
+    });
+}
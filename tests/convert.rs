@@ -0,0 +1,85 @@
+extern crate named_vec;
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
+use named_vec::*;
+use std::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+mod common;
+use common::*;
+
+fn letters() -> NamedVec<NamedNumber> {
+    named_vec![
+        NamedNumber::new("b", 0),
+        NamedNumber::new("a", 1),
+        NamedNumber::new("c", 2),
+    ]
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hash_map_round_trip() {
+    let map: HashMap<String, NamedNumber> = letters().into();
+    assert_eq!(map["a"].num(), 1);
+
+    let named_vec = NamedVec::try_from(map).unwrap();
+    assert!(named_vec.eq_ignoring_order(&letters()));
+}
+
+#[test]
+fn btree_map_round_trip() {
+    let map = BTreeMap::from(letters());
+    let named_vec = NamedVec::try_from(map).unwrap();
+
+    let names: Vec<&str> = (&named_vec).into_iter().map(|n| n.name()).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+}
+
+#[test]
+fn mismatched_keys() {
+    let mut map: BTreeMap<String, NamedNumber> = letters().into_map();
+    map.insert("d".to_owned(), NamedNumber::new("e", 3));
+    map.insert("z".to_owned(), NamedNumber::new("a", 4));
+
+    assert_eq!(NamedVec::try_from(map), Err(FromMapError {
+        mismatches: vec![
+            KeyMismatch { key: "d".to_owned(), name: "e".to_owned() },
+            KeyMismatch { key: "z".to_owned(), name: "a".to_owned() },
+        ],
+    }));
+}
+
+#[test]
+fn from_map_with_names() {
+    let mut map = BTreeMap::new();
+    map.insert("x".to_owned(), 1);
+    map.insert("y".to_owned(), 2);
+
+    let named_vec = NamedVec::from_map_with_names(map, NamedNumber::new).unwrap();
+    assert_eq!(named_vec["y"].num(), 2);
+    assert_eq!(named_vec.check_invariants(), Ok(()));
+}
+
+#[test]
+fn from_pairs_with_repeated_key() {
+    let pairs = vec![("x".to_owned(), 1), ("y".to_owned(), 2), ("x".to_owned(), 3)];
+
+    let named_vec = NamedVec::from_map_with_names(pairs, NamedNumber::new).unwrap();
+    assert_eq!(named_vec.len(), 2);
+    assert_eq!(named_vec["x"].num(), 3);
+    assert_eq!(named_vec.check_invariants(), Ok(()));
+}
+
+#[cfg(feature = "indexmap")]
+#[test]
+fn index_map_keeps_order() {
+    use indexmap::IndexMap;
+
+    let map: IndexMap<String, NamedNumber> = letters().into();
+    let names: Vec<&str> = map.keys().map(String::as_str).collect();
+    assert_eq!(names, ["b", "a", "c"]);
+
+    assert_eq!(NamedVec::try_from(map), Ok(letters()));
+}
@@ -0,0 +1,74 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+fn cow_numbers() -> CowNamedVec<NamedNumber> {
+    CowNamedVec::from(numbers(50))
+}
+
+#[test]
+fn clone_shares_storage() {
+    let original = cow_numbers();
+    assert!(!original.is_shared());
+
+    let clone = original.clone();
+    assert!(original.is_shared());
+    assert!(clone.ptr_eq(&original));
+    assert_eq!(clone["n7"].num(), 7);
+}
+
+#[test]
+fn first_change_copies() {
+    let original = cow_numbers();
+    let mut clone = original.clone();
+
+    clone.push(NamedNumber::new("n7", 70));
+    assert!(!clone.ptr_eq(&original));
+    assert!(!clone.is_shared());
+    assert!(!original.is_shared());
+    assert_eq!(original["n7"].num(), 7);
+    assert_eq!(clone["n7"].num(), 70);
+
+    let before = &*clone as *const NamedVec<_>;
+    clone.remove("n8");
+    clone.get_mut("n9").unwrap();
+    assert_eq!(&*clone as *const NamedVec<_>, before);
+    assert_eq!(clone.len(), 49);
+}
+
+#[test]
+fn no_op_changes_do_not_copy() {
+    let original = cow_numbers();
+    let mut clone = original.clone();
+
+    clone.truncate(100);
+    assert!(clone.ptr_eq(&original));
+
+    clone.clear();
+    assert!(clone.is_empty());
+    assert_eq!(original.len(), 50);
+    assert!(clone.pop().is_none());
+}
+
+#[test]
+fn into_inner() {
+    let original = cow_numbers();
+    let clone = original.clone();
+
+    assert_eq!(clone.into_inner(), original.clone().into_inner());
+    assert!(!original.is_shared());
+}
+
+#[test]
+fn shared_clear_keeps_threshold() {
+    let mut named_vec = NamedVec::with_index_threshold(0);
+    named_vec.push(NamedNumber::new("foo", 0));
+    let original = CowNamedVec::from(named_vec);
+    let mut clone = original.clone();
+
+    clone.clear();
+    assert_eq!(clone.index_threshold(), 0);
+    assert_eq!(original.len(), 1);
+}
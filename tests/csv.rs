@@ -0,0 +1,78 @@
+extern crate named_vec;
+use named_vec::csv::{self, CsvError, Duplicate, Record};
+use named_vec::*;
+
+mod common;
+use common::*;
+
+fn parse_number(record: &Record) -> Result<NamedNumber, String> {
+    let num = record.get("num").unwrap();
+    num.parse()
+        .map(|num| NamedNumber::new(record.name(), num))
+        .map_err(|_| format!("invalid number {:?}", num))
+}
+
+#[test]
+fn read_rows_in_order() {
+    let input = "num,name\r\n0,foo\n\n1,\"b,\"\"a\"\"\nr\"\n";
+    let named_vec = csv::read(input, "name", parse_number).unwrap();
+
+    let mut expected = NamedVec::new();
+    expected.push(NamedNumber::new("foo", 0));
+    expected.push(NamedNumber::new("b,\"a\"\nr", 1));
+
+    assert_eq!(named_vec, expected);
+}
+
+#[test]
+fn duplicates_are_reported_with_lines() {
+    let input = "name,num\nfoo,0\nbar,1\nfoo,2\n\"bar\",3\n";
+    let result = csv::read(input, "name", parse_number);
+
+    assert_eq!(result, Err(CsvError::Duplicates(vec![
+        Duplicate { name: "foo".to_owned(), first_line: 2, line: 4 },
+        Duplicate { name: "bar".to_owned(), first_line: 3, line: 5 },
+    ])));
+}
+
+#[test]
+fn errors() {
+    assert_eq!(csv::read("name,num\n\"foo,0\n", "name", parse_number),
+               Err(CsvError::Syntax { line: 2, message: "unterminated quoted field" }));
+    assert_eq!(csv::read("label,num\nfoo,0\n", "name", parse_number),
+               Err(CsvError::MissingColumn("name".to_owned())));
+    assert_eq!(csv::read("name,num\nfoo\n", "name", parse_number),
+               Err(CsvError::FieldCount { line: 2, expected: 2, found: 1 }));
+    assert_eq!(csv::read("name,num\nfoo,x\n", "name", parse_number),
+               Err(CsvError::Record { line: 2, error: "invalid number \"x\"".to_owned() }));
+}
+
+#[test]
+fn write_then_read() {
+    let mut named_vec = NamedVec::new();
+    named_vec.push(NamedNumber::new("foo", 0));
+    named_vec.push(NamedNumber::new("with, comma", 1));
+    named_vec.push(NamedNumber::new("\"quoted\"", 2));
+
+    let mut output = String::new();
+    csv::write(&mut output, &named_vec, &["name", "num"], |item| {
+        vec![item.name().to_owned(), item.num().to_string()]
+    }).unwrap();
+
+    assert_eq!(output, "name,num\nfoo,0\n\"with, comma\",1\n\"\"\"quoted\"\"\",2\n");
+    assert_eq!(csv::read(&output, "name", parse_number), Ok(named_vec));
+}
+
+#[test]
+fn empty_only_field_round_trips() {
+    let named_vec = named_vec![NamedNumber::new("", 0), NamedNumber::new("x", 0)];
+
+    let mut output = String::new();
+    csv::write(&mut output, &named_vec, &["name"], |item| vec![item.name().to_owned()]).unwrap();
+
+    assert_eq!(output, "name\n\"\"\nx\n");
+    let read = csv::read(&output, "name", |record| {
+        Ok::<_, String>(NamedNumber::new(record.name(), 0))
+    });
+    assert_eq!(read, Ok(named_vec));
+}
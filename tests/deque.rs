@@ -0,0 +1,59 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn push_and_pop_at_both_ends() {
+    let mut deque = NamedVecDeque::new();
+    deque.push_back(NamedNumber::new("b", 1));
+    deque.push_front(NamedNumber::new("a", 0));
+    deque.push_back(NamedNumber::new("c", 2));
+    deque.push_front(NamedNumber::new("z", 3));
+    assert_eq!(names(&deque), ["z", "a", "b", "c"]);
+    assert_eq!(deque["b"], deque[2]);
+
+    assert_eq!(deque.pop_front().unwrap().name(), "z");
+    assert_eq!(deque.pop_back().unwrap().name(), "c");
+    assert_eq!(deque.get("a"), deque.get(0));
+    assert_eq!(deque.get("b"), deque.get(1));
+    assert!(deque.get("z").is_none());
+    assert!(deque.get(2).is_none());
+}
+
+#[test]
+fn push_replaces_in_place() {
+    let mut deque = NamedVecDeque::new();
+    deque.push_back(NamedNumber::new("a", 0));
+    deque.push_back(NamedNumber::new("b", 1));
+    deque.push_front(NamedNumber::new("b", 2));
+
+    assert_eq!(names(&deque), ["a", "b"]);
+    assert_eq!(deque["b"].num(), 2);
+}
+
+#[test]
+fn remove_from_middle() {
+    let mut deque = NamedVecDeque::new();
+    for (i, name) in ["a", "b", "c", "d", "e", "f"].iter().enumerate() {
+        deque.push_front(NamedNumber::new(name, i as i32));
+    }
+
+    assert_eq!(deque.remove("e").num(), 4);
+    assert_eq!(deque.remove(3).name(), "b");
+    assert_eq!(names(&deque), ["f", "d", "c", "a"]);
+    for (i, name) in names(&deque).into_iter().enumerate() {
+        assert_eq!(deque[name], deque[i]);
+    }
+}
+
+#[test]
+fn from_named_vec() {
+    let deque = NamedVecDeque::from(named_vec![NamedNumber::new("a", 0), NamedNumber::new("b", 1)]);
+
+    assert_eq!(format!("{:?}", deque), format!("{:?}", named_vec![
+        NamedNumber::new("a", 0),
+        NamedNumber::new("b", 1),
+    ]));
+}
@@ -44,3 +44,37 @@ fn updating() {
 
     assert_eq!(first, second);
 }
+
+#[test]
+fn equality_ignoring_order() {
+    let first = named_vec![NamedNumber::new("foo", 1), NamedNumber::new("bar", 0)];
+    let second = named_vec![NamedNumber::new("bar", 0), NamedNumber::new("foo", 1)];
+    let third = named_vec![NamedNumber::new("bar", 1), NamedNumber::new("foo", 1)];
+
+    assert_ne!(first, second);
+    assert!(first.eq_ignoring_order(&second));
+    assert!(!first.eq_ignoring_order(&third));
+    assert!(!first.eq_ignoring_order(&named_vec![NamedNumber::new("foo", 1)]));
+}
+
+#[test]
+fn ordering_and_hashing() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let first = named_vec![NamedNumber::new("a", 0), NamedNumber::new("b", 0)];
+    let second = named_vec![NamedNumber::new("a", 0), NamedNumber::new("c", 0)];
+    let prefix = named_vec![NamedNumber::new("a", 0)];
+
+    assert!(prefix < first);
+    assert!(first < second);
+
+    let sorted: Vec<_> = vec![second.clone(), first.clone(), prefix.clone()]
+        .into_iter()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    assert_eq!(sorted, [prefix, first.clone(), second]);
+
+    let set: HashSet<_> = vec![first.clone(), first].into_iter().collect();
+    assert_eq!(set.len(), 1);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "std")]
+
+extern crate named_vec;
+use named_vec::expiry::{Clock, ManualClock};
+use named_vec::*;
+use std::time::Duration;
+
+mod common;
+use common::*;
+
+fn secs(n: u64) -> Duration {
+    Duration::from_secs(n)
+}
+
+#[test]
+fn expired_entries_are_missing() {
+    let clock = ManualClock::new();
+    let mut expiring = Expiring::with_clock(clock.clone());
+    expiring.push_with_ttl(NamedNumber::new("short", 0), secs(10));
+    expiring.push_with_ttl(NamedNumber::new("long", 1), secs(60));
+    expiring.push(NamedNumber::new("forever", 2));
+
+    clock.advance(secs(10));
+    assert!(expiring.get("short").is_none());
+    assert!(expiring.get(0).is_none());
+    assert!(expiring.get(3).is_none());
+    assert_eq!(expiring.get("long").unwrap().num(), 1);
+    assert_eq!(expiring.iter().count(), 2);
+    assert_eq!(expiring.len(), 3);
+}
+
+#[test]
+fn replacing_resets_deadline() {
+    let clock = ManualClock::new();
+    let mut expiring = Expiring::with_clock(clock.clone());
+    expiring.push_with_ttl(NamedNumber::new("foo", 0), secs(10));
+    expiring.push(NamedNumber::new("foo", 1));
+
+    clock.advance(secs(20));
+    assert_eq!(expiring.get("foo").unwrap().num(), 1);
+    assert!(expiring.deadline("foo").is_none());
+}
+
+#[test]
+fn huge_durations_saturate() {
+    let clock = ManualClock::new();
+    let mut expiring = Expiring::with_clock(clock.clone());
+    expiring.push_with_ttl(NamedNumber::new("forever", 0), Duration::MAX);
+    expiring.push_with_ttl(NamedNumber::new("short", 1), secs(10));
+
+    clock.advance(Duration::MAX);
+    clock.advance(Duration::MAX);
+    assert_eq!(expiring.get("forever").unwrap().num(), 0);
+    assert!(expiring.deadline("forever").is_none());
+    assert!(expiring.get("short").is_none());
+}
+
+#[test]
+fn purge_keeps_index_correct() {
+    let clock = ManualClock::new();
+    let mut expiring = Expiring::with_clock(clock.clone());
+    for i in 0..40 {
+        expiring.push_with_ttl(NamedNumber::new(&i.to_string(), i), secs(i as u64 % 3 + 1));
+    }
+
+    clock.advance(secs(2));
+    let purged = expiring.purge_expired(clock.now());
+    assert_eq!(purged.len(), 27);
+    assert_eq!(purged[1].name(), "1");
+    assert_eq!(expiring.len(), 13);
+
+    let named_vec = expiring.into_inner();
+    assert_eq!(named_vec.check_invariants(), Ok(()));
+    assert_eq!(named_vec[0].name(), "2");
+    assert_eq!(named_vec["5"], named_vec[1]);
+    assert!(named_vec.get("4").is_none());
+}
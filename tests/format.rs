@@ -0,0 +1,35 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn debug_is_map_in_index_order() {
+    let mut named_vec = NamedVec::with_index_threshold(0);
+    named_vec.push(NamedNumber::new("foo", 0));
+    named_vec.push(NamedNumber::new("bar", 1));
+
+    assert_eq!(
+        format!("{:?}", named_vec),
+        r#"{"foo": NamedNumber { name: "foo", num: 0 }, "bar": NamedNumber { name: "bar", num: 1 }}"#
+    );
+}
+
+#[test]
+fn display_table() {
+    let named_vec = named_vec![
+        NamedNumber::new("Mercury", 0),
+        NamedNumber::new("Earth", 1),
+    ];
+    let table = named_vec.display_table(&["moons", "parity"], |n| {
+        vec![n.num().to_string(), if n.num() % 2 == 0 { "even" } else { "odd" }.to_owned()]
+    });
+
+    assert_eq!(table.to_string(), "\
+#  name     moons  parity
+-  -------  -----  ------
+0  Mercury  0      even
+1  Earth    1      odd
+");
+}
@@ -38,7 +38,7 @@ fn invalid_index_trait() {
     named_vec.push(NamedNumber::new("bar", 1));
     named_vec.push(NamedNumber::new("foo", 0));
 
-    let ref _number = named_vec[3];
+    let _number = &named_vec[3];
 }
 
 #[test]
@@ -0,0 +1,46 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+fn parity(n: &NamedNumber) -> &'static str {
+    if n.num() % 2 == 0 { "even" } else { "odd" }
+}
+
+#[test]
+fn group_by_reference() {
+    let numbers = numbers(8);
+    let groups = numbers.group_by(parity);
+
+    let names: Vec<&str> = (&groups).into_iter().map(|g| g.name()).collect();
+    assert_eq!(names, ["even", "odd"]);
+
+    let odd: Vec<i32> = groups["odd"].members().into_iter().map(|n| n.num()).collect();
+    assert_eq!(odd, [1, 3, 5, 7]);
+    assert_eq!(groups["even"].members()["n4"].num(), 4);
+}
+
+#[test]
+fn group_by_borrowed_key() {
+    let numbers = named_vec![NamedNumber::new("apple", 0), NamedNumber::new("avocado", 1)];
+    let groups = numbers.group_by(|n| &n.name()[..1]);
+
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups["a"].members().len(), 2);
+}
+
+#[test]
+fn into_group_by() {
+    let groups = numbers(8).into_group_by(|n| (n.num() % 3).to_string());
+
+    let names: Vec<&str> = (&groups).into_iter().map(|g| g.name()).collect();
+    assert_eq!(names, ["0", "1", "2"]);
+
+    let members = groups["0"].clone().into_members();
+    assert_eq!(members, named_vec![
+        NamedNumber::new("n0", 0),
+        NamedNumber::new("n3", 3),
+        NamedNumber::new("n6", 6),
+    ]);
+}
@@ -0,0 +1,61 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn handle_survives_insert_and_swap() {
+    let mut named_vec = NamedVec::new();
+    let foo = named_vec.push_with_handle(NamedNumber::new("foo", 0));
+    named_vec.push(NamedNumber::new("bar", 1));
+
+    named_vec.insert(0, NamedNumber::new("baz", 2));
+    named_vec.swap("foo", "bar");
+
+    assert_eq!(named_vec.get(foo).unwrap().num(), 0);
+    assert_eq!(named_vec.handle(2), Some(foo));
+}
+
+#[test]
+fn handle_survives_remove_of_other_item() {
+    let mut named_vec = NamedVec::new();
+    named_vec.push(NamedNumber::new("foo", 0));
+    let bar = named_vec.push_with_handle(NamedNumber::new("bar", 1));
+
+    named_vec.remove("foo");
+
+    assert_eq!(named_vec.get(bar).unwrap().num(), 1);
+}
+
+#[test]
+fn stale_handle_after_remove() {
+    let mut named_vec = NamedVec::new();
+    let foo = named_vec.push_with_handle(NamedNumber::new("foo", 0));
+
+    named_vec.remove(foo);
+    let bar = named_vec.push_with_handle(NamedNumber::new("bar", 1));
+
+    assert!(named_vec.get(foo).is_none());
+    assert_eq!(named_vec.get(bar).unwrap().num(), 1);
+}
+
+#[test]
+fn replacing_keeps_handle() {
+    let mut named_vec = NamedVec::new();
+    let first = named_vec.push_with_handle(NamedNumber::new("foo", 0));
+    let second = named_vec.insert_with_handle(0, NamedNumber::new("foo", 1));
+
+    assert_eq!(first, second);
+    assert_eq!(named_vec.get(first).unwrap().num(), 1);
+}
+
+#[test]
+#[should_panic]
+fn remove_with_stale_handle() {
+    let mut named_vec = NamedVec::new();
+    let foo = named_vec.push_with_handle(NamedNumber::new("foo", 0));
+    named_vec.pop();
+
+    named_vec.remove(foo);
+}
@@ -0,0 +1,56 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+fn build(threshold: usize) -> NamedVec<NamedNumber> {
+    let mut named_vec = NamedVec::with_index_threshold(threshold);
+    for i in 0..10 {
+        named_vec.push(NamedNumber::new(&format!("item{}", i), i));
+    }
+    named_vec.insert(3, NamedNumber::new("inserted", 100));
+    named_vec.remove("item5");
+    named_vec.swap("item0", "item9");
+    named_vec.pop();
+    named_vec.push(NamedNumber::new("item2", 200));
+    named_vec
+}
+
+#[test]
+fn same_results_with_and_without_index() {
+    let scanned = build(usize::MAX);
+    let indexed = build(0);
+    let crossed = build(5);
+
+    assert_eq!(scanned, indexed);
+    assert_eq!(scanned, crossed);
+    for item in &scanned {
+        assert_eq!(indexed.get(item.name()), Some(item));
+        assert_eq!(crossed.get(item.name()), Some(item));
+    }
+}
+
+#[test]
+fn lookups_after_crossing_threshold() {
+    let mut named_vec = NamedVec::with_index_threshold(2);
+    named_vec.push(NamedNumber::new("foo", 0));
+    named_vec.push(NamedNumber::new("bar", 1));
+    named_vec.insert(0, NamedNumber::new("baz", 2));
+
+    assert_eq!(named_vec["foo"].num(), 0);
+    assert_eq!(named_vec["bar"].num(), 1);
+    assert_eq!(named_vec["baz"].num(), 2);
+    assert!(named_vec.get("quux").is_none());
+}
+
+#[test]
+fn lowering_threshold() {
+    let mut named_vec = NamedVec::new();
+    named_vec.push(NamedNumber::new("foo", 0));
+    named_vec.push(NamedNumber::new("bar", 1));
+    named_vec.set_index_threshold(0);
+
+    assert_eq!(named_vec.index_threshold(), 0);
+    assert_eq!(named_vec["bar"].num(), 1);
+}
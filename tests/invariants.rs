@@ -0,0 +1,57 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn holds_after_mutations() {
+    for &threshold in &[0, 16] {
+        let mut named_vec = NamedVec::with_index_threshold(threshold);
+        for i in 0..30 {
+            named_vec.push(NamedNumber::new(&format!("n{}", i), i));
+        }
+        named_vec.insert(3, NamedNumber::new("inserted", 0));
+        named_vec.remove("n7");
+        named_vec.swap(0, 20);
+        named_vec.pop();
+        named_vec.truncate(10);
+
+        assert_eq!(named_vec.check_invariants(), Ok(()));
+    }
+}
+
+#[test]
+fn renaming_through_get_mut() {
+    #[derive(Debug)]
+    struct Renamable(String);
+
+    impl Named for Renamable {
+        fn name(&self) -> &str {
+            &self.0
+        }
+    }
+
+    let mut named_vec = NamedVec::with_index_threshold(0);
+    named_vec.push(Renamable("foo".to_owned()));
+    named_vec.push(Renamable("bar".to_owned()));
+    named_vec.get_mut("foo").unwrap().0 = "baz".to_owned();
+
+    assert_eq!(named_vec.check_invariants(), Err(InvariantViolation::MissingEntry {
+        name: "baz".to_owned(),
+        index: 0,
+    }));
+}
+
+#[test]
+fn duplicates_from_macro() {
+    let named_vec = named_vec![
+        NamedNumber::new("foo", 0),
+        NamedNumber::new("bar", 1),
+        NamedNumber::new("foo", 2),
+    ];
+
+    assert_eq!(named_vec.len(), 2);
+    assert_eq!(named_vec["foo"].num(), 0);
+    assert_eq!(named_vec.check_invariants(), Ok(()));
+}
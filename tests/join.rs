@@ -0,0 +1,78 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq)]
+struct Label(&'static str);
+
+impl Named for Label {
+    fn name(&self) -> &str {
+        self.0
+    }
+}
+
+fn desired() -> NamedVec<NamedNumber> {
+    named_vec![
+        NamedNumber::new("a", 1),
+        NamedNumber::new("b", 2),
+        NamedNumber::new("c", 3),
+    ]
+}
+
+fn actual() -> NamedVec<Label> {
+    named_vec![Label("d"), Label("c"), Label("a"), Label("e")]
+}
+
+fn names<'a, A, B>(iter: impl Iterator<Item = (&'a str, Option<A>, Option<B>)>)
+    -> Vec<(&'a str, bool, bool)> {
+    iter.map(|(name, a, b)| (name, a.is_some(), b.is_some())).collect()
+}
+
+#[test]
+fn inner() {
+    let (desired, actual) = (desired(), actual());
+    let joined: Vec<_> = desired.join(&actual).inner().collect();
+
+    assert_eq!(joined, [
+        ("a", Some(&desired["a"]), Some(&Label("a"))),
+        ("c", Some(&desired["c"]), Some(&Label("c"))),
+    ]);
+}
+
+#[test]
+fn left_outer() {
+    let (desired, actual) = (desired(), actual());
+
+    assert_eq!(names(desired.join(&actual).left_outer()), [
+        ("a", true, true),
+        ("b", true, false),
+        ("c", true, true),
+    ]);
+}
+
+#[test]
+fn full_outer() {
+    let (desired, actual) = (desired(), actual());
+
+    assert_eq!(names(desired.join(&actual).full_outer()), [
+        ("a", true, true),
+        ("b", true, false),
+        ("c", true, true),
+        ("d", false, true),
+        ("e", false, true),
+    ]);
+}
+
+#[test]
+fn large_collections_use_index() {
+    let left: NamedVec<_> = (0..100).map(|i| NamedNumber::new(&i.to_string(), i)).collect();
+    let right: NamedVec<_> = (50..150).rev().map(|i| NamedNumber::new(&i.to_string(), i)).collect();
+
+    assert_eq!(left.join(&right).inner().count(), 50);
+    assert_eq!(left.join(&right).left_outer().count(), 100);
+    let full: Vec<_> = left.join(&right).full_outer().map(|(name, _, _)| name).collect();
+    assert_eq!(full.len(), 150);
+    assert_eq!(full[100], "149");
+}
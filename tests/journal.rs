@@ -0,0 +1,139 @@
+extern crate named_vec;
+use named_vec::journal::Edit;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+use std::panic::{self, AssertUnwindSafe};
+
+#[test]
+fn undo_and_redo_each_operation() {
+    let mut journaled = Journaled::new();
+    journaled.push(NamedNumber::new("foo", 0));
+    journaled.push(NamedNumber::new("bar", 1));
+    journaled.insert(1, NamedNumber::new("baz", 2));
+    journaled.push(NamedNumber::new("foo", 3));
+    journaled.swap("foo", "bar");
+    journaled.remove("baz");
+    journaled.pop();
+    journaled.clear();
+
+    let mut states = Vec::new();
+    loop {
+        states.push((*journaled).clone());
+        if !journaled.undo() {
+            break;
+        }
+    }
+    assert!(journaled.is_empty());
+    assert_eq!(names(&states[1][..]), ["bar"]);
+    assert_eq!(names(&states[3][..]), ["bar", "baz", "foo"]);
+    assert_eq!(names(&states[5][..]), ["foo", "baz", "bar"]);
+
+    states.pop();
+    while journaled.redo() {
+        assert_eq!(names(&journaled[..]), names(&states.pop().unwrap()[..]));
+    }
+    assert!(states.is_empty());
+    assert_eq!(journaled.into_inner(), NamedVec::new());
+}
+
+#[test]
+fn undo_replacement() {
+    let mut journaled = Journaled::new();
+    journaled.push(NamedNumber::new("foo", 0));
+    journaled.push(NamedNumber::new("foo", 1));
+
+    journaled.undo();
+    assert_eq!(journaled["foo"].num(), 0);
+}
+
+#[test]
+fn transaction_is_one_step() {
+    let mut journaled = Journaled::new();
+    journaled.push(NamedNumber::new("foo", 0));
+    journaled.transaction(|j| {
+        j.push(NamedNumber::new("bar", 1));
+        j.push(NamedNumber::new("baz", 2));
+        j.remove("foo");
+    });
+
+    assert_eq!(names(&journaled[..]), ["bar", "baz"]);
+    journaled.undo();
+    assert_eq!(names(&journaled[..]), ["foo"]);
+    journaled.redo();
+    assert_eq!(names(&journaled[..]), ["bar", "baz"]);
+}
+
+#[test]
+fn transaction_rolls_back_on_panic() {
+    let mut journaled = Journaled::new();
+    journaled.push(NamedNumber::new("foo", 0));
+    let checkpoint = journaled.checkpoint();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        journaled.transaction(|j| {
+            j.push(NamedNumber::new("bar", 1));
+            j.remove("foo");
+            panic!("oops");
+        })
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(names(&journaled[..]), ["foo"]);
+    assert_eq!(journaled.changes_since(checkpoint).len(), 4);
+
+    assert!(journaled.undo());
+    assert!(journaled.is_empty());
+    assert!(journaled.redo());
+    assert_eq!(names(&journaled[..]), ["foo"]);
+}
+
+#[test]
+fn rolled_back_transaction_keeps_redo() {
+    let mut journaled = Journaled::new();
+    journaled.push(NamedNumber::new("foo", 0));
+    journaled.undo();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        journaled.transaction(|j| {
+            j.push(NamedNumber::new("bar", 1));
+            panic!("oops");
+        })
+    }));
+
+    assert!(result.is_err());
+    assert!(journaled.can_redo());
+    assert!(journaled.redo());
+    assert_eq!(names(&journaled[..]), ["foo"]);
+
+    journaled.undo();
+    journaled.transaction(|j| j.push(NamedNumber::new("bar", 1)));
+    assert!(!journaled.can_redo());
+}
+
+#[test]
+fn new_change_clears_redo() {
+    let mut journaled = Journaled::new();
+    journaled.push(NamedNumber::new("foo", 0));
+    journaled.undo();
+    journaled.push(NamedNumber::new("bar", 1));
+
+    assert!(!journaled.can_redo());
+    assert!(!journaled.redo());
+}
+
+#[test]
+fn changes_since_checkpoint() {
+    let mut journaled = Journaled::new();
+    journaled.push(NamedNumber::new("foo", 0));
+    let checkpoint = journaled.checkpoint();
+    journaled.push(NamedNumber::new("bar", 1));
+    journaled.undo();
+
+    assert_eq!(journaled.changes_since(checkpoint), &[
+        Edit::Inserted { index: 1, item: NamedNumber::new("bar", 1) },
+        Edit::Removed { index: 1, item: NamedNumber::new("bar", 1) },
+    ]);
+}
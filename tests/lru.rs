@@ -0,0 +1,81 @@
+extern crate named_vec;
+use named_vec::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+mod common;
+use common::*;
+
+#[test]
+fn evicts_least_recently_used() {
+    let mut lru = NamedLru::new(3);
+    assert!(lru.push(NamedNumber::new("a", 0)).is_none());
+    assert!(lru.push(NamedNumber::new("b", 1)).is_none());
+    assert!(lru.push(NamedNumber::new("c", 2)).is_none());
+    assert_eq!(names(&lru), ["c", "b", "a"]);
+
+    assert_eq!(lru.get("a").unwrap().num(), 0);
+    assert_eq!(lru.push(NamedNumber::new("d", 3)), Some(NamedNumber::new("b", 1)));
+    assert_eq!(names(&lru), ["d", "a", "c"]);
+    assert_eq!(lru.len(), 3);
+}
+
+#[test]
+fn peek_does_not_promote() {
+    let mut lru = NamedLru::new(2);
+    lru.push(NamedNumber::new("a", 0));
+    lru.push(NamedNumber::new("b", 1));
+
+    assert_eq!(lru.peek("a").unwrap().num(), 0);
+    assert_eq!(lru.peek_lru().unwrap().name(), "a");
+    assert_eq!(lru.push(NamedNumber::new("c", 2)).unwrap().name(), "a");
+    assert!(!lru.contains("a"));
+}
+
+#[test]
+fn replacing_promotes() {
+    let mut lru = NamedLru::new(2);
+    lru.push(NamedNumber::new("a", 0));
+    lru.push(NamedNumber::new("b", 1));
+
+    assert!(lru.push(NamedNumber::new("a", 2)).is_none());
+    assert_eq!(names(&lru), ["a", "b"]);
+    *lru.get_mut("b").unwrap() = NamedNumber::new("b", 3);
+    assert_eq!(names(&lru), ["b", "a"]);
+    assert_eq!(lru.peek("b").unwrap().num(), 3);
+}
+
+#[test]
+fn remove_and_reuse() {
+    let mut lru = NamedLru::new(3);
+    lru.push(NamedNumber::new("a", 0));
+    lru.push(NamedNumber::new("b", 1));
+    lru.push(NamedNumber::new("c", 2));
+
+    assert_eq!(lru.remove("b").unwrap().num(), 1);
+    assert!(lru.remove("b").is_none());
+    assert_eq!(lru.pop_lru().unwrap().name(), "a");
+    lru.push(NamedNumber::new("d", 3));
+    lru.push(NamedNumber::new("e", 4));
+    assert_eq!(names(&lru), ["e", "d", "c"]);
+
+    lru.clear();
+    assert!(lru.is_empty());
+    assert!(lru.peek_lru().is_none());
+}
+
+#[test]
+fn eviction_callback() {
+    let evicted = Rc::new(RefCell::new(Vec::new()));
+    let mut lru = NamedLru::new(1);
+    let log = evicted.clone();
+    lru.on_evict(move |n: &NamedNumber| log.borrow_mut().push(n.name().to_owned()));
+
+    lru.push(NamedNumber::new("a", 0));
+    lru.push(NamedNumber::new("b", 1));
+    lru.remove("b");
+    lru.push(NamedNumber::new("c", 2));
+    lru.push(NamedNumber::new("d", 3));
+
+    assert_eq!(*evicted.borrow(), ["a", "c"]);
+}
@@ -0,0 +1,68 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+#[derive(Debug, PartialEq)]
+struct Doubled {
+    name: String,
+    value: i64,
+}
+
+impl Named for Doubled {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+fn double(n: NamedNumber) -> Doubled {
+    Doubled { name: n.name().to_owned(), value: i64::from(n.num()) * 2 }
+}
+
+#[test]
+fn map_keeps_names_and_handles() {
+    for &count in &[5, 50] {
+        let named_vec = numbers(count);
+        let handle = named_vec.handle("n3").unwrap();
+        let mapped = named_vec.map(double);
+
+        assert_eq!(mapped.len(), count as usize);
+        assert_eq!(mapped["n3"].value, 6);
+        assert_eq!(mapped[4].name(), "n4");
+        assert_eq!(mapped.get(handle).unwrap().name(), "n3");
+        assert_eq!(mapped.check_invariants(), Ok(()));
+    }
+}
+
+#[test]
+#[should_panic(expected = "renamed element 2")]
+fn map_detects_rename() {
+    numbers(5).map(|n| NamedNumber::new(if n.num() == 2 { "x" } else { n.name() }, 0));
+}
+
+#[test]
+fn try_map_reports_failing_name() {
+    for &count in &[5, 50] {
+        let result = numbers(count).try_map(|n| {
+            if n.num() == 3 { Err("three") } else { Ok(double(n)) }
+        });
+
+        assert_eq!(result, Err(MapError::Failed { name: "n3".to_owned(), error: "three" }));
+    }
+}
+
+#[test]
+fn try_map_reports_rename() {
+    for &count in &[5, 50] {
+        let result = numbers(count).try_map(|n| {
+            Ok::<_, ()>(NamedNumber::new(if n.num() == 1 { "n0" } else { n.name() }, 0))
+        });
+
+        assert_eq!(result, Err(MapError::NameMismatch {
+            index: 1,
+            expected: "n1".to_owned(),
+            found: "n0".to_owned(),
+        }));
+    }
+}
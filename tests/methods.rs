@@ -1,4 +1,3 @@
-#[macro_use]
 extern crate named_vec;
 use named_vec::*;
 
@@ -0,0 +1,77 @@
+extern crate named_vec;
+use named_vec::observe::Change;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn record(observed: &mut Observed<NamedNumber>) -> Rc<RefCell<Vec<String>>> {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let log = events.clone();
+    observed.subscribe(move |change: &Change<NamedNumber>| {
+        let event = match *change {
+            Change::Inserted { index, name } => format!("inserted {} at {}", name, index),
+            Change::Removed { index, item } => format!("removed {} from {}", item.name(), index),
+            Change::Replaced { index, old } => format!("replaced {} at {}", old.num(), index),
+            Change::Moved { from, to } => format!("moved {} to {}", from, to),
+            Change::Cleared => "cleared".to_owned(),
+        };
+        log.borrow_mut().push(event);
+    });
+    events
+}
+
+#[test]
+fn every_mutator_notifies() {
+    let mut observed = Observed::new();
+    let events = record(&mut observed);
+
+    observed.push(NamedNumber::new("foo", 0));
+    observed.push(NamedNumber::new("bar", 1));
+    observed.insert(0, NamedNumber::new("baz", 2));
+    observed.push(NamedNumber::new("foo", 3));
+    observed.insert(2, NamedNumber::new("bar", 4));
+    observed.swap("baz", "bar");
+    observed.remove("foo");
+    observed.pop();
+    observed.push(NamedNumber::new("quux", 5));
+    observed.truncate(1);
+    observed.clear();
+
+    assert_eq!(*events.borrow(), [
+        "inserted foo at 0",
+        "inserted bar at 1",
+        "inserted baz at 0",
+        "replaced 0 at 1",
+        "replaced 1 at 2",
+        "moved 0 to 2",
+        "moved 2 to 0",
+        "removed foo from 1",
+        "removed baz from 1",
+        "inserted quux at 1",
+        "removed quux from 1",
+        "cleared",
+    ]);
+    assert!(observed.is_empty());
+}
+
+#[test]
+fn unsubscribe() {
+    let mut observed = Observed::new();
+    let count = Rc::new(RefCell::new(0));
+    let counter = count.clone();
+    let subscription = observed.subscribe(move |_: &Change<NamedNumber>| {
+        *counter.borrow_mut() += 1;
+    });
+
+    observed.push(NamedNumber::new("foo", 0));
+    assert!(observed.unsubscribe(subscription));
+    assert!(!observed.unsubscribe(subscription));
+    observed.push(NamedNumber::new("bar", 1));
+
+    assert_eq!(*count.borrow(), 1);
+    assert_eq!(observed.len(), 2);
+}
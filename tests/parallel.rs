@@ -0,0 +1,38 @@
+#![cfg(feature = "rayon")]
+
+extern crate named_vec;
+extern crate rayon;
+use named_vec::*;
+use rayon::prelude::*;
+
+mod common;
+use common::*;
+
+fn with_duplicates() -> Vec<NamedNumber> {
+    (0..2_000).map(|i| NamedNumber::new(&format!("n{}", i * 7 % 200), i)).collect()
+}
+
+#[test]
+fn collect_matches_sequential() {
+    let sequential: NamedVec<_> = with_duplicates().into_iter().collect();
+    let parallel: NamedVec<_> = with_duplicates().into_par_iter().collect();
+
+    assert_eq!(parallel.len(), 200);
+    assert_eq!(parallel, sequential);
+    assert_eq!(parallel.check_invariants(), Ok(()));
+    assert_eq!(parallel["n7"].num(), 1_801);
+}
+
+#[test]
+fn iterate_in_parallel() {
+    let mut named_vec: NamedVec<_> = (0..100).map(|i| NamedNumber::new(&i.to_string(), i)).collect();
+
+    let sum: i32 = named_vec.par_iter().map(NamedNumber::num).sum();
+    assert_eq!(sum, 4_950);
+
+    named_vec.par_iter_mut().for_each(|n| *n = NamedNumber::new(n.name(), n.num() * 2));
+    assert_eq!(named_vec["10"].num(), 20);
+
+    let names: Vec<String> = named_vec.into_par_iter().map(|n| n.name().to_owned()).collect();
+    assert_eq!(names[99], "99");
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "persistent")]
+
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn old_versions_are_unchanged() {
+    let empty = PersistentNamedVec::new();
+    let first = empty.push(NamedNumber::new("foo", 0));
+    let second = first.push(NamedNumber::new("bar", 1));
+    let third = second.remove("foo");
+
+    assert!(empty.is_empty());
+    assert_eq!(first.len(), 1);
+    assert_eq!(second.get("foo"), Some(&NamedNumber::new("foo", 0)));
+    assert_eq!(third.get("foo"), None);
+    assert_eq!(third.get(0), Some(&NamedNumber::new("bar", 1)));
+}
+
+#[test]
+fn insert_updates_indices() {
+    let vec = PersistentNamedVec::new()
+        .push(NamedNumber::new("foo", 0))
+        .push(NamedNumber::new("bar", 1))
+        .insert(1, NamedNumber::new("baz", 2));
+
+    assert_eq!(vec.get(1), Some(&NamedNumber::new("baz", 2)));
+    assert_eq!(vec.get("bar"), Some(&NamedNumber::new("bar", 1)));
+    assert_eq!(vec.remove("baz").get("bar"), vec.get(2));
+}
+
+#[test]
+fn push_replaces_existing_name() {
+    let vec = PersistentNamedVec::new()
+        .push(NamedNumber::new("foo", 0))
+        .push(NamedNumber::new("foo", 1));
+
+    assert_eq!(vec.len(), 1);
+    assert_eq!(vec.get("foo"), Some(&NamedNumber::new("foo", 1)));
+}
+
+#[test]
+fn set_with_new_name() {
+    let old = PersistentNamedVec::new().push(NamedNumber::new("foo", 0));
+    let new = old.set("foo", NamedNumber::new("bar", 1));
+
+    assert_eq!(new.get("foo"), None);
+    assert_eq!(new.get("bar"), Some(&NamedNumber::new("bar", 1)));
+    assert_eq!(old.get("foo"), Some(&NamedNumber::new("foo", 0)));
+}
+
+#[test]
+fn from_named_vec() {
+    let mut named_vec = NamedVec::new();
+    named_vec.push(NamedNumber::new("foo", 0));
+    named_vec.push(NamedNumber::new("bar", 1));
+
+    let persistent = PersistentNamedVec::from(named_vec);
+
+    assert_eq!(persistent.get("bar").unwrap().num(), 1);
+}
+
+#[test]
+fn many_inserts_in_one_place() {
+    let mut persistent = PersistentNamedVec::new()
+        .push(NamedNumber::new("first", 0))
+        .push(NamedNumber::new("last", 0));
+    let mut named_vec = NamedVec::new();
+    named_vec.push(NamedNumber::new("first", 0));
+    named_vec.push(NamedNumber::new("last", 0));
+
+    for i in 0..200 {
+        let item = NamedNumber::new(&format!("n{}", i), i);
+        persistent = persistent.insert(1, item.clone());
+        named_vec.insert(1, item);
+        if i % 3 == 0 {
+            persistent = persistent.remove(1);
+            named_vec.remove(1);
+        }
+    }
+
+    assert!(persistent.iter().eq(&named_vec));
+    for (i, item) in named_vec.into_iter().enumerate() {
+        assert_eq!(persistent.get(item.name()), persistent.get(i));
+    }
+}
@@ -0,0 +1,81 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+fn by_num() -> SortedNamedVec<NamedNumber, impl Fn(&NamedNumber) -> i32> {
+    let mut sorted = SortedNamedVec::new(NamedNumber::num);
+    for &(name, num) in &[("c", 3), ("a", 1), ("e", 5), ("b", 2), ("b2", 2), ("d", 4)] {
+        sorted.push(NamedNumber::new(name, num));
+    }
+    sorted
+}
+
+#[test]
+fn push_keeps_order() {
+    let sorted = by_num();
+
+    assert_eq!(names(&sorted[..]), ["a", "b", "b2", "c", "d", "e"]);
+    assert_eq!(sorted.first().unwrap().name(), "a");
+    assert_eq!(sorted.last().unwrap().name(), "e");
+    assert_eq!(sorted["d"].num(), 4);
+    assert_eq!(sorted.rank("c"), Some(3));
+    assert_eq!(sorted.rank("z"), None);
+}
+
+#[test]
+fn replacing_moves_element() {
+    let mut sorted = by_num();
+    sorted.push(NamedNumber::new("a", 10));
+
+    assert_eq!(names(&sorted[..]), ["b", "b2", "c", "d", "e", "a"]);
+    assert_eq!(sorted.len(), 6);
+    assert_eq!(sorted.rank("a"), Some(5));
+}
+
+#[test]
+fn search_and_ranges() {
+    let sorted = by_num();
+
+    assert_eq!(sorted.binary_search_by_key(&4), Ok(4));
+    assert_eq!(sorted.binary_search_by_key(&0), Err(0));
+    assert_eq!(sorted.binary_search_by_key(&9), Err(6));
+
+    assert_eq!(names(sorted.range_by_key(2..4)), ["b", "b2", "c"]);
+    assert_eq!(names(sorted.range_by_key(2..=2)), ["b", "b2"]);
+    assert_eq!(names(sorted.range_by_key(..2)), ["a"]);
+    assert_eq!(names(sorted.range_by_key(5..)), ["e"]);
+    assert!(sorted.range_by_key(7..9).is_empty());
+}
+
+#[test]
+fn pop_and_remove() {
+    let mut sorted = by_num();
+
+    assert_eq!(sorted.pop_first().unwrap().name(), "a");
+    assert_eq!(sorted.pop_last().unwrap().name(), "e");
+    assert_eq!(sorted.remove("b2").num(), 2);
+    assert_eq!(names(&sorted[..]), ["b", "c", "d"]);
+    assert_eq!(sorted.rank("d"), Some(2));
+}
+
+#[test]
+fn sorted_by_name() {
+    let mut sorted = SortedNamedVec::new(|n: &NamedNumber| n.name().to_owned());
+    sorted.push(NamedNumber::new("beta", 0));
+    sorted.push(NamedNumber::new("alpha", 1));
+
+    assert_eq!(names(&sorted[..]), ["alpha", "beta"]);
+}
+
+#[test]
+fn capturing_closure() {
+    let target = 3;
+    let mut sorted = SortedNamedVec::new(move |n: &NamedNumber| (n.num() - target).abs());
+    for &(name, num) in &[("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 6)] {
+        sorted.push(NamedNumber::new(name, num));
+    }
+
+    assert_eq!(names(&sorted[..]), ["c", "b", "d", "a", "e"]);
+}
@@ -0,0 +1,105 @@
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+extern crate named_vec;
+#[cfg(feature = "proptest")]
+extern crate proptest;
+#[cfg(feature = "quickcheck")]
+extern crate quickcheck;
+use named_vec::testing::{check_model, Op};
+use named_vec::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn model_agrees() {
+    let initial = named_vec![NamedNumber::new("foo", 0), NamedNumber::new("bar", 1)];
+    let ops = [
+        Op::Push(NamedNumber::new("baz", 2)),
+        Op::Insert(7, NamedNumber::new("foo", 3)),
+        Op::Swap(0, 5),
+        Op::Remove(4),
+        Op::Truncate(2),
+        Op::Pop,
+        Op::Pop,
+        Op::Pop,
+        Op::Remove(0),
+        Op::Insert(0, NamedNumber::new("qux", 4)),
+    ];
+
+    assert_eq!(check_model(&initial, &ops), Ok(()));
+}
+
+#[cfg(feature = "proptest")]
+mod proptest_model {
+    use named_vec::testing::{check_model, strategy};
+    use proptest::prelude::*;
+
+    use common::*;
+
+    fn number() -> impl Strategy<Value = NamedNumber> + Clone {
+        ("[a-e]", any::<i32>()).prop_map(|(name, num)| NamedNumber::new(&name, num))
+    }
+
+    proptest! {
+        #[test]
+        fn model_agrees(
+            initial in strategy::named_vec(number(), 0..30),
+            ops in prop::collection::vec(strategy::op(number()), 0..50),
+        ) {
+            prop_assert!(initial.check_invariants().is_ok());
+            prop_assert_eq!(check_model(&initial, &ops), Ok(()));
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+mod quickcheck_model {
+    use named_vec::testing::{check_model, Op};
+    use named_vec::NamedVec;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    use common::*;
+
+    impl Arbitrary for NamedNumber {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let name = *g.choose(&["a", "b", "c", "d", "e"]).unwrap();
+            NamedNumber::new(name, i32::arbitrary(g))
+        }
+    }
+
+    #[test]
+    fn model_agrees() {
+        fn property(initial: NamedVec<NamedNumber>, ops: Vec<Op<NamedNumber>>) -> bool {
+            initial.check_invariants().is_ok() && check_model(&initial, &ops).is_ok()
+        }
+        quickcheck(property as fn(_, _) -> bool);
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_model {
+    use arbitrary::{Arbitrary, Result, Unstructured};
+    use named_vec::testing::{check_model, Op};
+    use named_vec::NamedVec;
+
+    use common::*;
+
+    impl<'a> Arbitrary<'a> for NamedNumber {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let name = *u.choose(&["a", "b", "c", "d", "e"])?;
+            Ok(NamedNumber::new(name, u.arbitrary()?))
+        }
+    }
+
+    #[test]
+    fn model_agrees() {
+        let bytes: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect();
+        let mut u = Unstructured::new(&bytes);
+        let initial: NamedVec<NamedNumber> = u.arbitrary().unwrap();
+        let ops: Vec<Op<NamedNumber>> = u.arbitrary().unwrap();
+
+        assert!(initial.check_invariants().is_ok());
+        assert_eq!(check_model(&initial, &ops), Ok(()));
+    }
+}
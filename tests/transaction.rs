@@ -0,0 +1,172 @@
+extern crate named_vec;
+use named_vec::*;
+
+mod common;
+use common::*;
+
+use std::panic::{self, AssertUnwindSafe};
+
+fn sample() -> NamedVec<NamedNumber> {
+    let mut named_vec = NamedVec::new();
+    named_vec.push(NamedNumber::new("foo", 0));
+    named_vec.push(NamedNumber::new("bar", 1));
+    named_vec.push(NamedNumber::new("baz", 2));
+    named_vec
+}
+
+#[test]
+fn commit() {
+    let mut named_vec = sample();
+
+    let removed = named_vec.transaction(|tx| {
+        tx.push(NamedNumber::new("quux", 3));
+        tx.swap("foo", "baz")?;
+        tx.insert(0, NamedNumber::new("bar", 4))?;
+        tx.remove("foo").map(|item| item.num())
+    });
+
+    let mut expected = NamedVec::new();
+    expected.push(NamedNumber::new("baz", 2));
+    expected.push(NamedNumber::new("bar", 4));
+    expected.push(NamedNumber::new("quux", 3));
+
+    assert_eq!(removed, Ok(0));
+    assert_eq!(named_vec, expected);
+    assert_eq!(named_vec["quux"].num(), 3);
+}
+
+#[test]
+fn rollback_on_error() {
+    let mut named_vec = sample();
+    let handle = named_vec.handle("baz").unwrap();
+
+    let result: Result<(), LookupError> = named_vec.transaction(|tx| {
+        tx.remove("baz")?;
+        tx.insert(1, NamedNumber::new("quux", 3))?;
+        tx.truncate(1);
+        tx.swap("foo", "missing")
+    });
+
+    assert_eq!(result, Err(LookupError::Name("missing".to_owned())));
+    assert_eq!(named_vec, sample());
+    assert_eq!(named_vec.get(handle).unwrap().num(), 2);
+    assert!(named_vec.get("quux").is_none());
+}
+
+#[test]
+fn rollback_on_panic() {
+    let mut named_vec = sample();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        named_vec.transaction(|tx| -> Result<(), LookupError> {
+            tx.clear();
+            tx.push(NamedNumber::new("quux", 3));
+            panic!("oops");
+        })
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(named_vec, sample());
+    assert_eq!(named_vec["bar"].num(), 1);
+}
+
+#[test]
+fn indexed_vec_after_commit() {
+    let mut named_vec = NamedVec::with_index_threshold(0);
+    named_vec.push(NamedNumber::new("foo", 0));
+
+    named_vec.transaction(|tx| -> Result<(), LookupError> {
+        tx.insert(0, NamedNumber::new("bar", 1))?;
+        assert_eq!(tx["foo"].num(), 0);
+        Ok(())
+    }).unwrap();
+
+    assert_eq!(named_vec["foo"].num(), 0);
+    assert_eq!(named_vec["bar"].num(), 1);
+}
+
+#[test]
+fn index_is_used_inside_transaction() {
+    let mut named_vec = NamedVec::with_index_threshold(0);
+    named_vec.push(NamedNumber::new("foo", 0));
+    named_vec.push(NamedNumber::new("bar", 1));
+
+    named_vec.transaction(|tx| -> Result<(), LookupError> {
+        tx.clear();
+        for i in 0..100 {
+            tx.push(NamedNumber::new(&format!("n{}", i), i));
+        }
+        tx.remove("n10")?;
+        assert!(tx.get("foo").is_none());
+        assert_eq!(tx["n50"].num(), 50);
+        Ok(())
+    }).unwrap();
+
+    assert_eq!(named_vec.len(), 99);
+    assert_eq!(named_vec.check_invariants(), Ok(()));
+
+    let result = named_vec.transaction(|tx| -> Result<(), LookupError> {
+        tx.truncate(5);
+        tx.push(NamedNumber::new("foo", 0));
+        tx.remove("n50")?;
+        Ok(())
+    });
+
+    assert_eq!(result, Err(LookupError::Name("n50".to_owned())));
+    assert_eq!(named_vec.len(), 99);
+    assert_eq!(named_vec["n50"].num(), 50);
+    assert_eq!(named_vec.check_invariants(), Ok(()));
+}
+
+#[test]
+fn invalid_insert_index() {
+    let mut named_vec = sample();
+
+    let result = named_vec.transaction(|tx| tx.insert(5, NamedNumber::new("quux", 3)));
+
+    assert_eq!(result, Err(LookupError::Index(5)));
+}
+
+#[test]
+fn get_mut_is_rolled_back() {
+    let mut named_vec = sample();
+
+    let result = named_vec.transaction(|tx| {
+        *tx.get_mut("bar")? = NamedNumber::new("bar", 10);
+        assert_eq!(tx["bar"].num(), 10);
+        tx.get_mut("quux").map(|_| ())
+    });
+
+    assert_eq!(result, Err(LookupError::Name("quux".to_owned())));
+    assert_eq!(named_vec, sample());
+}
+
+#[test]
+fn handles_survive_rollback() {
+    let mut named_vec = NamedVec::with_index_threshold(0);
+    for i in 0..6 {
+        named_vec.push(NamedNumber::new(&format!("n{}", i), i));
+    }
+    named_vec.remove("n4");
+    let handles: Vec<Handle> = (0..5).map(|i| named_vec.handle(i).unwrap()).collect();
+    let before = named_vec.clone();
+
+    let result = named_vec.transaction(|tx| -> Result<(), LookupError> {
+        tx.remove("n1")?;
+        tx.insert(0, NamedNumber::new("new", 10))?;
+        tx.swap(0, 3)?;
+        tx.push(NamedNumber::new("last", 11));
+        tx.truncate(2);
+        tx.push(NamedNumber::new("n5", 12));
+        Err(LookupError::Index(99))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(named_vec, before);
+    assert_eq!(named_vec.check_invariants(), Ok(()));
+    for (i, &handle) in handles.iter().enumerate() {
+        assert_eq!(named_vec.handle(i), Some(handle));
+    }
+    let handle = named_vec.push_with_handle(NamedNumber::new("after", 13));
+    assert!(handles.iter().all(|&h| h != handle));
+}